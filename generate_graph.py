@@ -39,7 +39,7 @@ def draw_failing(graph, data):
             label += " -R"
             fillcolor = "orange"
 
-        if node['has_transmute']:
+        if node['mutability_laundering']:
             label += " -T"
             fillcolor = "orange"
 
@@ -1,39 +1,305 @@
 use itertools::Itertools;
 use regex::Regex;
-use rustc_middle::mir::{Mutability, VarDebugInfoContents};
+use rustc_middle::mir::{Local, Mutability, VarDebugInfoContents};
 use rustc_middle::ty::TyCtxt;
 use rustc_span::symbol::Symbol;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 
 use crate::analyzer::{
+    cache::{CachedVerdict, LocalVerdict, VerdictCache, VerdictKey},
     deps::compute_deps_for_body,
-    heuristics::{HasRawPtrDeref, HasTransmute},
-    result::{FunctionWithMetadata, PurityAnalysisResult},
+    heuristics::{
+        check_foreign_call, FfiSummary, HasGlobalStateWrite, HasInteriorMutation,
+        HasLeakingRawPtrWrite, HasMutabilityLaundering, HasRawPtrDeref, RawPtrHeuristic,
+    },
+    result::{FunctionWithMetadata, PurityAnalysisResult, WitnessHop},
 };
 use crate::body_cache::substituted_mir;
 use crate::common::storage::{ClosureInfoStorage, FunctionInfoStorage};
-use crate::common::FunctionInfo;
+use crate::common::{readable_path, FailureReason, FunctionInfo};
 use crate::important::ImportantLocals;
 
+// Replay a verdict and all verdicts below it into the results of the current region, skipping
+// those that were already recorded.
+fn record_verdict<'tcx>(
+    id: usize,
+    cache: &VerdictCache<'tcx>,
+    recorded: &mut HashSet<usize>,
+    passing_calls_ref: &mut Vec<FunctionWithMetadata<'tcx>>,
+    failing_calls_ref: &mut Vec<FunctionWithMetadata<'tcx>>,
+    deps: &mut HashSet<String>,
+) {
+    if !recorded.insert(id) {
+        return;
+    }
+    let verdict = cache.get(id);
+    for child_id in verdict.children() {
+        record_verdict(
+            *child_id,
+            cache,
+            recorded,
+            passing_calls_ref,
+            failing_calls_ref,
+            deps,
+        );
+    }
+    deps.extend(verdict.deps().iter().cloned());
+    if verdict.is_pure() {
+        passing_calls_ref.push(verdict.function_with_metadata().to_owned());
+    } else {
+        failing_calls_ref.push(verdict.function_with_metadata().to_owned());
+    }
+}
+
+// Find the shortest chain of calls from the origin to a failing function whose callees all pass.
+fn compute_witness<'tcx>(origin_id: usize, cache: &VerdictCache<'tcx>) -> Vec<WitnessHop<'tcx>> {
+    let is_failing = |id: &usize| !cache.get(*id).is_pure();
+    if !is_failing(&origin_id) {
+        return vec![];
+    }
+
+    let mut predecessors = HashMap::from([(origin_id, None)]);
+    let mut queue = VecDeque::from([origin_id]);
+    let mut leaf_id = origin_id;
+    while let Some(id) = queue.pop_front() {
+        let failing_children = cache
+            .get(id)
+            .children()
+            .iter()
+            .filter(|child_id| is_failing(child_id))
+            .collect_vec();
+        if failing_children.is_empty() {
+            leaf_id = id;
+            break;
+        }
+        for child_id in failing_children {
+            if !predecessors.contains_key(child_id) {
+                predecessors.insert(*child_id, Some(id));
+                queue.push_back(*child_id);
+            }
+        }
+    }
+
+    let mut witness = vec![];
+    let mut current = Some(leaf_id);
+    while let Some(id) = current {
+        witness.push(WitnessHop::new(
+            cache.get(id).function_with_metadata().to_owned(),
+            cache.key(id).important_args().to_owned(),
+        ));
+        current = predecessors[&id];
+    }
+    witness.reverse();
+    witness
+}
+
 fn analyze_item<'tcx>(
     item: &FunctionInfo<'tcx>,
-    important_locals: ImportantLocals,
+    important_args: Vec<usize>,
+    depth: usize,
+    passing_calls_ref: &mut Vec<FunctionWithMetadata<'tcx>>,
+    failing_calls_ref: &mut Vec<FunctionWithMetadata<'tcx>>,
+    deps: &mut HashSet<String>,
+    recorded: &mut HashSet<usize>,
+    cache: &mut VerdictCache<'tcx>,
+    storage: &FunctionInfoStorage<'tcx>,
+    allowlist: &Vec<Regex>,
+    trusted_stdlib: &Vec<Regex>,
+    raw_ptr_heuristic: RawPtrHeuristic,
+    ffi_summaries: &BTreeMap<String, FfiSummary>,
+    tcx: TyCtxt<'tcx>,
+) -> usize {
+    let key = VerdictKey::new(item, &important_args);
+    let id = match cache.lookup(&key, item) {
+        Some(id) => id,
+        None if !tcx.recursion_limit().value_within_limit(depth) => {
+            // Give up on call chains that are too deep, without computing the important locals of
+            // the body. The verdict depends on the depth, so it is neither reused by later lookups
+            // nor persisted.
+            let info_with_metadata = FunctionWithMetadata::new(
+                item.to_owned(),
+                ImportantLocals::from_locals(
+                    important_args.into_iter().map(Local::from_usize).collect(),
+                ),
+                false,
+                false,
+                vec![],
+                false,
+                false,
+                vec![FailureReason::RecursionLimit],
+            );
+            let verdict =
+                CachedVerdict::new(false, info_with_metadata, HashSet::new(), vec![], true);
+            cache.insert(key, verdict)
+        }
+        None => {
+            let verdict = compute_verdict(
+                item,
+                important_args,
+                depth,
+                passing_calls_ref,
+                failing_calls_ref,
+                deps,
+                recorded,
+                cache,
+                storage,
+                allowlist,
+                trusted_stdlib,
+                raw_ptr_heuristic,
+                ffi_summaries,
+                tcx,
+            );
+            cache.insert(key, verdict)
+        }
+    };
+    record_verdict(
+        id,
+        cache,
+        recorded,
+        passing_calls_ref,
+        failing_calls_ref,
+        deps,
+    );
+    id
+}
+
+fn compute_verdict<'tcx>(
+    item: &FunctionInfo<'tcx>,
+    important_args: Vec<usize>,
+    depth: usize,
     passing_calls_ref: &mut Vec<FunctionWithMetadata<'tcx>>,
     failing_calls_ref: &mut Vec<FunctionWithMetadata<'tcx>>,
     deps: &mut HashSet<String>,
+    recorded: &mut HashSet<usize>,
+    cache: &mut VerdictCache<'tcx>,
     storage: &FunctionInfoStorage<'tcx>,
     allowlist: &Vec<Regex>,
     trusted_stdlib: &Vec<Regex>,
+    raw_ptr_heuristic: RawPtrHeuristic,
+    ffi_summaries: &BTreeMap<String, FfiSummary>,
     tcx: TyCtxt<'tcx>,
-) -> bool {
-    if let Some(instance) = item.instance() {
-        let body = substituted_mir(&instance, tcx);
-        deps.extend(compute_deps_for_body(body, tcx).into_iter());
+) -> CachedVerdict<'tcx> {
+    // Only the analysis of the function itself is reused from summaries. Calls are always
+    // followed, so that the results are the same as the ones of a fresh run.
+    let (local_verdict, from_summary) = match cache.load_summary(item, &important_args, tcx) {
+        Some(local_verdict) => (local_verdict, true),
+        None => {
+            let local_verdict = compute_local_verdict(
+                item,
+                important_args.clone(),
+                allowlist,
+                trusted_stdlib,
+                raw_ptr_heuristic,
+                ffi_summaries,
+                tcx,
+            );
+            (local_verdict, false)
+        }
+    };
+
+    let children = match item.calls() {
+        Some(calls) if local_verdict.follows_calls() => calls
+            .iter()
+            .map(|call| {
+                let new_important_args = local_verdict
+                    .function_with_metadata()
+                    .important_locals()
+                    .important_args_to_callee(call.args(), call.def_id().to_owned(), tcx);
+                let call_fn_info = storage.get_by_call(call);
+                analyze_item(
+                    call_fn_info,
+                    new_important_args,
+                    depth + 1,
+                    passing_calls_ref,
+                    failing_calls_ref,
+                    deps,
+                    recorded,
+                    cache,
+                    storage,
+                    allowlist,
+                    trusted_stdlib,
+                    raw_ptr_heuristic,
+                    ffi_summaries,
+                    tcx,
+                )
+            })
+            .collect_vec(),
+        _ => vec![],
+    };
+
+    let hit_recursion_limit = children
+        .iter()
+        .any(|child| cache.get(*child).hit_recursion_limit());
+    if !from_summary && !hit_recursion_limit {
+        cache.store_summary(item, &important_args, &local_verdict, tcx);
+    }
+
+    // Leaking implementors of erased `dyn` calls are named, as the call itself does not tell
+    // which of them is the culprit.
+    let mut info_with_metadata = local_verdict.function_with_metadata().to_owned();
+    let mut has_leaking_call = false;
+    if let Some(calls) = item.calls().filter(|_| local_verdict.follows_calls()) {
+        for (call, child) in calls.iter().zip(children.iter()) {
+            if cache.get(*child).is_pure() {
+                continue;
+            }
+            match call.implementor_of() {
+                Some(..) => info_with_metadata.add_failure_reason(
+                    FailureReason::LeakingImplementor(tcx.def_path_str(call.def_id())),
+                ),
+                None => has_leaking_call = true,
+            }
+        }
+    }
+    if has_leaking_call {
+        info_with_metadata.add_failure_reason(FailureReason::LeakingCall);
     }
+    let pure = info_with_metadata.failure_reasons().is_empty();
+    CachedVerdict::new(
+        pure,
+        info_with_metadata,
+        local_verdict.deps().to_owned(),
+        children,
+        hit_recursion_limit,
+    )
+}
+
+// Analyze the function without looking at its callees.
+fn compute_local_verdict<'tcx>(
+    item: &FunctionInfo<'tcx>,
+    important_args: Vec<usize>,
+    allowlist: &Vec<Regex>,
+    trusted_stdlib: &Vec<Regex>,
+    raw_ptr_heuristic: RawPtrHeuristic,
+    ffi_summaries: &BTreeMap<String, FfiSummary>,
+    tcx: TyCtxt<'tcx>,
+) -> LocalVerdict<'tcx> {
+    let item_deps = match item.instance() {
+        Some(instance) => {
+            let body = substituted_mir(&instance, tcx);
+            compute_deps_for_body(body, tcx)
+        }
+        None => HashSet::new(),
+    };
+
+    let important_locals = ImportantLocals::from_important_args(important_args, item.def_id(), tcx);
+
+    // Allowlist and trusted stdlib entries can be written against either the debug format of the
+    // `DefId` or the readable path of the function.
+    let def_path_strs = [
+        format!("{:?}", item.def_id()),
+        readable_path(item.def_id(), tcx),
+    ];
+    let matches_any = |patterns: &Vec<Regex>| {
+        patterns.iter().any(|pattern| {
+            def_path_strs
+                .iter()
+                .any(|def_path_str| pattern.is_match(def_path_str))
+        })
+    };
 
     let is_trusted = {
-        let def_path_str = format!("{:?}", item.def_id());
-        let trusted_stdlib_member = trusted_stdlib.iter().any(|lib| lib.is_match(&def_path_str));
+        let trusted_stdlib_member = matches_any(trusted_stdlib);
         let self_ty = item.instance().and_then(|instance| {
             let body = substituted_mir(&instance, tcx);
             body.var_debug_info
@@ -55,13 +321,12 @@ fn analyze_item<'tcx>(
                 )
             })
             .unwrap_or(false);
+        // Methods taking `&self` can mutate the receiver through interior mutability, so they are
+        // analyzed even if they belong to the trusted part of the standard library.
         trusted_stdlib_member && !has_immut_self_ref
     };
 
-    let is_allowlisted = {
-        let def_path_str = format!("{:?}", item.def_id());
-        allowlist.iter().any(|lib| lib.is_match(&def_path_str))
-    };
+    let is_allowlisted = matches_any(allowlist);
 
     let has_no_important_locals = important_locals.is_empty();
 
@@ -71,10 +336,12 @@ fn analyze_item<'tcx>(
             important_locals.clone(),
             false,
             is_allowlisted,
+            vec![],
+            false,
             false,
+            vec![],
         );
-        passing_calls_ref.push(info_with_metadata);
-        true
+        LocalVerdict::new(info_with_metadata, item_deps, false)
     } else {
         let has_unhandled_calls = match item {
             FunctionInfo::WithBody { unhandled, .. } => !unhandled.is_empty(),
@@ -82,73 +349,97 @@ fn analyze_item<'tcx>(
         };
 
         let has_raw_pointer_deref = match item {
-            FunctionInfo::WithBody { body, .. } => body.has_raw_ptr_deref(tcx),
+            FunctionInfo::WithBody { body, .. } => match raw_ptr_heuristic {
+                RawPtrHeuristic::Coarse => body.has_raw_ptr_deref(tcx),
+                RawPtrHeuristic::Dataflow => {
+                    body.has_leaking_raw_ptr_write(item.def_id(), &important_locals, tcx)
+                }
+            },
             _ => false,
         };
 
-        let has_transmute = match item {
-            FunctionInfo::WithBody { body, .. } => body.has_transmute(tcx),
+        let mutability_laundering = match item {
+            FunctionInfo::WithBody { body, .. } => body.mutability_laundering(tcx),
+            _ => vec![],
+        };
+
+        let has_interior_mutation = match item {
+            FunctionInfo::WithBody { body, .. } => {
+                body.has_interior_mutation(&important_locals, tcx)
+            }
             _ => false,
         };
 
-        let has_no_leaking_calls = item
-            .calls()
-            .and_then(|calls| {
-                let children_results = calls
-                    .iter()
-                    .map(|call| {
-                        let new_important_locals =
-                            important_locals.transition(call.args(), call.def_id().to_owned(), tcx);
-                        let call_fn_info = storage.get_by_call(call);
-                        analyze_item(
-                            call_fn_info,
-                            new_important_locals,
-                            passing_calls_ref,
-                            failing_calls_ref,
-                            deps,
-                            storage,
-                            allowlist,
-                            trusted_stdlib,
-                            tcx,
-                        )
-                    })
-                    .collect_vec();
-                Some(children_results.into_iter().all(|r| r))
-            })
+        let has_inline_asm = item
+            .inline_asm()
+            .map(|inline_asm| !inline_asm.is_empty())
             .unwrap_or(false);
 
-        if !has_unhandled_calls && !has_raw_pointer_deref && !has_transmute && has_no_leaking_calls
-        {
-            let info_with_metadata = FunctionWithMetadata::new(
-                item.to_owned(),
-                important_locals.clone(),
-                has_raw_pointer_deref,
-                is_allowlisted,
-                has_transmute,
-            );
-            passing_calls_ref.push(info_with_metadata);
-            true
-        } else {
-            let info_with_metadata = FunctionWithMetadata::new(
-                item.to_owned(),
-                important_locals.clone(),
-                has_raw_pointer_deref,
-                is_allowlisted,
-                has_transmute,
-            );
-            failing_calls_ref.push(info_with_metadata);
-            false
+        let global_state_write = match item {
+            FunctionInfo::WithBody { body, .. } => {
+                body.has_global_state_write(&important_locals, tcx)
+            }
+            _ => false,
+        };
+
+        let mut failure_reasons = vec![];
+        if has_unhandled_calls {
+            failure_reasons.push(FailureReason::UnhandledCallType);
+        }
+        if has_raw_pointer_deref {
+            failure_reasons.push(FailureReason::RawPointerWrite);
         }
+        if !mutability_laundering.is_empty() {
+            failure_reasons.push(FailureReason::MutableTransmute);
+        }
+        if has_interior_mutation {
+            failure_reasons.push(FailureReason::InteriorMutation);
+        }
+        if global_state_write {
+            failure_reasons.push(FailureReason::GlobalStateWrite);
+        }
+        if has_inline_asm {
+            failure_reasons.push(FailureReason::InlineAsm);
+        }
+        if item.calls().is_none() {
+            if tcx.is_foreign_item(item.def_id()) {
+                // Foreign functions are classified by their name or by the summaries from the
+                // config.
+                if let Err(reason) = check_foreign_call(item.def_id(), ffi_summaries, tcx) {
+                    failure_reasons.push(reason);
+                }
+            } else if tcx.is_intrinsic(item.def_id()) {
+                // Functions without a body cannot be analyzed unless they are allowlisted.
+                failure_reasons.push(FailureReason::IntrinsicNotAllowlisted);
+            } else {
+                failure_reasons.push(FailureReason::NoMirAvailable);
+            }
+        }
+
+        let info_with_metadata = FunctionWithMetadata::new(
+            item.to_owned(),
+            important_locals.clone(),
+            has_raw_pointer_deref,
+            is_allowlisted,
+            mutability_laundering,
+            has_interior_mutation,
+            global_state_write,
+            failure_reasons,
+        );
+        LocalVerdict::new(info_with_metadata, item_deps, true)
     }
 }
 
 pub fn run<'tcx>(
     functions: FunctionInfoStorage<'tcx>,
     closures: ClosureInfoStorage<'tcx>,
-    important_locals: ImportantLocals,
+    important_args: Vec<usize>,
     annotated_pure: bool,
     allowlist: &Vec<Regex>,
     trusted_stdlib: &Vec<Regex>,
+    raw_ptr_heuristic: RawPtrHeuristic,
+    ffi_summaries: &BTreeMap<String, FfiSummary>,
+    cache: &mut VerdictCache<'tcx>,
     tcx: TyCtxt<'tcx>,
 ) -> PurityAnalysisResult<'tcx> {
     let origin = functions.get_with_body(functions.origin()).unwrap();
@@ -156,40 +447,54 @@ pub fn run<'tcx>(
     let mut passing_calls = vec![];
     let mut failing_calls = vec![];
     let mut deps = HashSet::new();
+    let mut recorded = HashSet::new();
 
-    let pure = analyze_item(
+    let origin_id = analyze_item(
         origin,
-        important_locals,
+        important_args,
+        0,
         &mut passing_calls,
         &mut failing_calls,
         &mut deps,
+        &mut recorded,
+        cache,
         &functions,
         allowlist,
         trusted_stdlib,
+        raw_ptr_heuristic,
+        ffi_summaries,
         tcx,
     );
+    let pure = cache.get(origin_id).is_pure();
+    let witness = compute_witness(origin_id, cache);
 
-    if pure {
-        PurityAnalysisResult::new(
-            functions.origin().def_id(),
-            annotated_pure,
-            true,
-            String::new(),
-            passing_calls,
-            failing_calls,
-            closures,
-            deps,
-        )
+    // The region fails for the reason of the function at the end of the witness, unless a
+    // leaking implementor of an erased `dyn` call on the way names the culprit.
+    let reason = if pure {
+        None
     } else {
-        PurityAnalysisResult::new(
-            functions.origin().def_id(),
-            annotated_pure,
-            false,
-            String::from("unable to ascertain purity of inner function call"),
-            passing_calls,
-            failing_calls,
-            closures,
-            deps,
-        )
-    }
+        witness
+            .iter()
+            .flat_map(|hop| hop.function().failure_reasons().iter())
+            .find(|reason| matches!(reason, FailureReason::LeakingImplementor(..)))
+            .or_else(|| {
+                witness
+                    .last()
+                    .and_then(|hop| hop.function().failure_reasons().first())
+            })
+            .cloned()
+            .or(Some(FailureReason::LeakingCall))
+    };
+
+    PurityAnalysisResult::new(
+        functions.origin().to_owned(),
+        annotated_pure,
+        pure,
+        reason,
+        passing_calls,
+        failing_calls,
+        closures,
+        deps,
+        witness,
+    )
 }
@@ -0,0 +1,206 @@
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::{self, TyCtxt};
+use std::collections::{HashMap, HashSet};
+
+use crate::analyzer::result::FunctionWithMetadata;
+use crate::analyzer::summary::SummaryStore;
+use crate::common::FunctionInfo;
+
+// Identifies a verdict by the analyzed function and the argument positions that carry important
+// data into it.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct VerdictKey<'tcx> {
+    def_id: DefId,
+    instance: Option<ty::Instance<'tcx>>,
+    important_args: Vec<usize>,
+}
+
+impl<'tcx> VerdictKey<'tcx> {
+    pub fn new(item: &FunctionInfo<'tcx>, important_args: &Vec<usize>) -> Self {
+        let mut important_args = important_args.to_owned();
+        important_args.sort();
+        important_args.dedup();
+        VerdictKey {
+            def_id: item.def_id(),
+            instance: item.instance(),
+            important_args,
+        }
+    }
+
+    pub fn important_args(&self) -> &Vec<usize> {
+        &self.important_args
+    }
+}
+
+// The part of a verdict that only depends on the function itself and not on its callees, which is
+// what gets persisted in summaries.
+pub struct LocalVerdict<'tcx> {
+    function_with_metadata: FunctionWithMetadata<'tcx>,
+    deps: HashSet<String>,
+    follows_calls: bool,
+}
+
+impl<'tcx> LocalVerdict<'tcx> {
+    pub fn new(
+        function_with_metadata: FunctionWithMetadata<'tcx>,
+        deps: HashSet<String>,
+        follows_calls: bool,
+    ) -> Self {
+        LocalVerdict {
+            function_with_metadata,
+            deps,
+            follows_calls,
+        }
+    }
+
+    pub fn function_with_metadata(&self) -> &FunctionWithMetadata<'tcx> {
+        &self.function_with_metadata
+    }
+
+    pub fn deps(&self) -> &HashSet<String> {
+        &self.deps
+    }
+
+    // Whether the callees need to be analyzed, which is not the case for allowlisted and trusted
+    // functions or functions without important locals.
+    pub fn follows_calls(&self) -> bool {
+        self.follows_calls
+    }
+}
+
+pub struct CachedVerdict<'tcx> {
+    pure: bool,
+    function_with_metadata: FunctionWithMetadata<'tcx>,
+    deps: HashSet<String>,
+    children: Vec<usize>,
+    hit_recursion_limit: bool,
+}
+
+impl<'tcx> CachedVerdict<'tcx> {
+    pub fn new(
+        pure: bool,
+        function_with_metadata: FunctionWithMetadata<'tcx>,
+        deps: HashSet<String>,
+        children: Vec<usize>,
+        hit_recursion_limit: bool,
+    ) -> Self {
+        CachedVerdict {
+            pure,
+            function_with_metadata,
+            deps,
+            children,
+            hit_recursion_limit,
+        }
+    }
+
+    pub fn is_pure(&self) -> bool {
+        self.pure
+    }
+
+    pub fn function_with_metadata(&self) -> &FunctionWithMetadata<'tcx> {
+        &self.function_with_metadata
+    }
+
+    pub fn deps(&self) -> &HashSet<String> {
+        &self.deps
+    }
+
+    pub fn children(&self) -> &Vec<usize> {
+        &self.children
+    }
+
+    // Whether the verdict or any verdict below it gave up because of the recursion limit, which
+    // makes it depend on the depth it was computed at.
+    pub fn hit_recursion_limit(&self) -> bool {
+        self.hit_recursion_limit
+    }
+}
+
+// Verdicts for every analyzed function, shared between all regions of a crate run.
+//
+// The same instance can be collected differently depending on the region it is reached from
+// (e.g. when its arguments are erased), so verdicts are only reused if the collected function
+// information matches exactly.
+//
+// Optionally, verdicts are also looked up in and recorded to on-disk summaries.
+pub struct VerdictCache<'tcx> {
+    verdicts: Vec<CachedVerdict<'tcx>>,
+    keys: Vec<VerdictKey<'tcx>>,
+    index: HashMap<VerdictKey<'tcx>, Vec<usize>>,
+    summaries: Option<SummaryStore>,
+}
+
+impl<'tcx> VerdictCache<'tcx> {
+    pub fn new() -> Self {
+        VerdictCache {
+            verdicts: vec![],
+            keys: vec![],
+            index: HashMap::new(),
+            summaries: None,
+        }
+    }
+
+    pub fn with_summaries(fingerprint: String, tcx: TyCtxt<'tcx>) -> Self {
+        VerdictCache {
+            summaries: Some(SummaryStore::load(fingerprint, tcx)),
+            ..VerdictCache::new()
+        }
+    }
+
+    pub fn load_summary(
+        &self,
+        item: &FunctionInfo<'tcx>,
+        important_args: &Vec<usize>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Option<LocalVerdict<'tcx>> {
+        self.summaries
+            .as_ref()
+            .and_then(|summaries| summaries.get(item, important_args, tcx))
+    }
+
+    pub fn store_summary(
+        &mut self,
+        item: &FunctionInfo<'tcx>,
+        important_args: &Vec<usize>,
+        verdict: &LocalVerdict<'tcx>,
+        tcx: TyCtxt<'tcx>,
+    ) {
+        if let Some(summaries) = self.summaries.as_mut() {
+            summaries.insert(item, important_args, verdict, tcx);
+        }
+    }
+
+    pub fn persist_summaries(&self, tcx: TyCtxt<'tcx>) {
+        if let Some(summaries) = self.summaries.as_ref() {
+            summaries.persist(tcx);
+        }
+    }
+
+    pub fn lookup(&self, key: &VerdictKey<'tcx>, item: &FunctionInfo<'tcx>) -> Option<usize> {
+        self.index.get(key).and_then(|ids| {
+            ids.iter()
+                .find(|id| self.verdicts[**id].function_with_metadata.function() == item)
+                .copied()
+        })
+    }
+
+    pub fn insert(&mut self, key: VerdictKey<'tcx>, verdict: CachedVerdict<'tcx>) -> usize {
+        let id = self.verdicts.len();
+        // Verdicts that depend on the call depth are kept for the current region, but are not
+        // found by later lookups.
+        if !verdict.hit_recursion_limit() {
+            self.index.entry(key.clone()).or_default().push(id);
+        }
+        self.verdicts.push(verdict);
+        self.keys.push(key);
+        id
+    }
+
+    pub fn get(&self, id: usize) -> &CachedVerdict<'tcx> {
+        &self.verdicts[id]
+    }
+
+    pub fn key(&self, id: usize) -> &VerdictKey<'tcx> {
+        &self.keys[id]
+    }
+}
@@ -0,0 +1,65 @@
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::TyCtxt;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::common::{readable_path, FailureReason, ForeignCallKind};
+
+// Math functions of libm that neither touch memory nor global state, without the `f` suffix of
+// their single-precision variants.
+const PURE_MATH: [&str; 30] = [
+    "sin", "cos", "tan", "asin", "acos", "atan", "atan2", "sinh", "cosh", "tanh", "asinh", "acosh",
+    "atanh", "sqrt", "cbrt", "hypot", "exp", "exp2", "expm1", "log", "log2", "log10", "log1p",
+    "pow", "fabs", "floor", "ceil", "round", "trunc", "fmod",
+];
+
+// Thin wrappers around system calls exposed by libc, without the `64` suffix of their large file
+// variants.
+const SYSCALL_WRAPPERS: [&str; 34] = [
+    "syscall", "read", "write", "pread", "pwrite", "readv", "writev", "open", "openat", "creat",
+    "close", "lseek", "fsync", "unlink", "rename", "mkdir", "rmdir", "ioctl", "fcntl", "mmap",
+    "munmap", "mprotect", "socket", "connect", "bind", "listen", "accept", "send", "sendto",
+    "recv", "recvfrom", "fork", "execve", "kill",
+];
+
+/// Purity of a foreign function declared in the config, which takes precedence over the built-in
+/// classification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FfiSummary {
+    Pure,
+    Impure,
+}
+
+// Check a call to a foreign function by its link name, e.g. `write`, or its readable path, e.g.
+// `libc::unix::write`.
+pub fn check_foreign_call(
+    def_id: DefId,
+    ffi_summaries: &BTreeMap<String, FfiSummary>,
+    tcx: TyCtxt,
+) -> Result<(), FailureReason> {
+    let link_name = tcx
+        .codegen_fn_attrs(def_id)
+        .link_name
+        .unwrap_or(tcx.item_name(def_id))
+        .to_string();
+    let summary = ffi_summaries
+        .get(&link_name)
+        .or_else(|| ffi_summaries.get(&readable_path(def_id, tcx)));
+    match summary {
+        Some(FfiSummary::Pure) => Ok(()),
+        Some(FfiSummary::Impure) => {
+            Err(FailureReason::ForeignCall(ForeignCallKind::DeclaredImpure))
+        }
+        None if is_one_of(&link_name, &PURE_MATH, "f") => Ok(()),
+        None if is_one_of(&link_name, &SYSCALL_WRAPPERS, "64") => {
+            Err(FailureReason::ForeignCall(ForeignCallKind::SyscallWrapper))
+        }
+        None => Err(FailureReason::ForeignCall(ForeignCallKind::Unknown)),
+    }
+}
+
+fn is_one_of(link_name: &str, names: &[&str], variant_suffix: &str) -> bool {
+    let base_name = link_name.strip_suffix(variant_suffix).unwrap_or(link_name);
+    names.contains(&link_name) || names.contains(&base_name)
+}
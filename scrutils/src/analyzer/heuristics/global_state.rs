@@ -0,0 +1,122 @@
+use regex::Regex;
+use rustc_middle::mir::{
+    visit::Visitor, Body, Local, Location, Operand, Statement, StatementKind, Terminator,
+    TerminatorKind,
+};
+use rustc_middle::ty::TyCtxt;
+
+use crate::analyzer::heuristics::origins::{is_local_key_constant, LocalCollector, LocalOrigins};
+use crate::common::readable_path;
+use crate::important::ImportantLocals;
+
+// Methods of `thread_local!` keys that modify the value of the current thread. `with` only hands
+// out a shared reference, so writes through it are left to the interior mutability heuristic.
+const THREAD_LOCAL_MUTATORS: &str =
+    r"^std::thread::local::LocalKey::<.*>::(with_borrow_mut|set|replace|take)$";
+
+struct GlobalStateWriteVisitor<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    important_locals: &'a ImportantLocals,
+    local_origins: LocalOrigins,
+    thread_local_mutators: Regex,
+    has_global_state_write: bool,
+}
+
+pub trait HasGlobalStateWrite<'tcx> {
+    fn has_global_state_write(&self, important_locals: &ImportantLocals, tcx: TyCtxt<'tcx>)
+        -> bool;
+}
+
+impl<'tcx> HasGlobalStateWrite<'tcx> for Body<'tcx> {
+    fn has_global_state_write(
+        &self,
+        important_locals: &ImportantLocals,
+        tcx: TyCtxt<'tcx>,
+    ) -> bool {
+        let mut global_state_write_visitor = GlobalStateWriteVisitor {
+            tcx,
+            important_locals,
+            local_origins: LocalOrigins::compute(self, tcx),
+            thread_local_mutators: Regex::new(THREAD_LOCAL_MUTATORS).unwrap(),
+            has_global_state_write: false,
+        };
+        global_state_write_visitor.visit_body(self);
+        global_state_write_visitor.has_global_state_write
+    }
+}
+
+impl<'a, 'tcx> GlobalStateWriteVisitor<'a, 'tcx> {
+    // Whether the local points to a `static mut` or to thread-local storage.
+    fn is_global_state(&self, local: Local) -> bool {
+        self.local_origins.is_thread_local(local)
+            || self
+                .local_origins
+                .static_of(local)
+                .map(|def_id| self.tcx.is_mutable_static(def_id))
+                .unwrap_or(false)
+    }
+
+    fn is_important(&self, local: Local) -> bool {
+        self.important_locals.locals().contains(&local)
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for GlobalStateWriteVisitor<'a, 'tcx> {
+    fn visit_statement(&mut self, statement: &Statement<'tcx>, location: Location) {
+        if let StatementKind::Assign(box (place, rvalue)) = &statement.kind {
+            if place.is_indirect() && self.is_global_state(place.local) {
+                let mut local_collector = LocalCollector::default();
+                local_collector.visit_rvalue(rvalue, location);
+                if local_collector
+                    .locals
+                    .iter()
+                    .any(|local| self.is_important(*local))
+                {
+                    self.has_global_state_write = true;
+                }
+            }
+        }
+        self.super_statement(statement, location);
+    }
+
+    fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location) {
+        if let TerminatorKind::Call { func, args, .. } = &terminator.kind {
+            let arg_locals = args
+                .iter()
+                .filter_map(|arg| arg.place().map(|place| place.local))
+                .collect::<Vec<_>>();
+            let passes_important = arg_locals.iter().any(|local| self.is_important(*local));
+
+            // Global state passed to a call together with important data, e.g. `GLOBAL.push(a)`.
+            let passes_global_state = arg_locals.iter().any(|local| self.is_global_state(*local));
+
+            // Thread locals declared with `thread_local!` are accessed through their key, which is
+            // usually passed as a promoted constant.
+            let mutates_thread_local = func
+                .const_fn_def()
+                .map(|(def_id, _)| {
+                    self.thread_local_mutators
+                        .is_match(&readable_path(def_id, self.tcx))
+                })
+                .unwrap_or(false)
+                && args
+                    .first()
+                    .map(|receiver| match receiver {
+                        Operand::Constant(constant) => {
+                            is_local_key_constant(constant, self.tcx)
+                                || constant.check_static_ptr(self.tcx).is_some()
+                        }
+                        Operand::Copy(place) | Operand::Move(place) => {
+                            self.local_origins.is_local_key(place.local)
+                                || self.local_origins.static_of(place.local).is_some()
+                        }
+                    })
+                    .unwrap_or(false);
+
+            if passes_important && (passes_global_state || mutates_thread_local) {
+                self.has_global_state_write = true;
+            }
+        }
+        self.super_terminator(terminator, location);
+    }
+}
@@ -0,0 +1,198 @@
+use regex::Regex;
+use rustc_middle::mir::{
+    visit::Visitor, Body, Local, Location, Operand, Statement, StatementKind, Terminator,
+    TerminatorKind,
+};
+use rustc_middle::ty::TyCtxt;
+use std::collections::HashSet;
+
+use crate::analyzer::heuristics::origins::{LocalCollector, LocalOrigins};
+use crate::common::readable_path;
+use crate::important::ImportantLocals;
+
+// Functions that write one of their arguments into an `UnsafeCell` behind a shared reference.
+const VALUE_MUTATORS: [&str; 3] = [
+    r"^core::cell::Cell::<.*>::(set|replace|swap)$",
+    r"^core::cell::RefCell::<.*>::(replace|replace_with|swap)$",
+    r"^core::sync::atomic::Atomic\w*(::<.*>)?::(store|swap|fetch_\w+|compare_exchange\w*|compare_and_swap)$",
+];
+
+// Functions that hand out write access to the contents of an `UnsafeCell` through a guard.
+const GUARD_MUTATORS: [&str; 2] = [
+    r"^core::cell::RefCell::<.*>::(borrow_mut|try_borrow_mut)$",
+    r"^std::sync::(\w+::)*(Mutex|RwLock)::<.*>::(lock|try_lock|write|try_write)$",
+];
+
+struct InteriorMutationVisitor<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'a Body<'tcx>,
+    important_locals: &'a ImportantLocals,
+    value_mutators: Vec<Regex>,
+    guard_mutators: Vec<Regex>,
+    local_origins: LocalOrigins,
+    guards: HashSet<Local>,
+    has_interior_mutation: bool,
+}
+
+pub trait HasInteriorMutation<'tcx> {
+    fn has_interior_mutation(&self, important_locals: &ImportantLocals, tcx: TyCtxt<'tcx>) -> bool;
+}
+
+impl<'tcx> HasInteriorMutation<'tcx> for Body<'tcx> {
+    fn has_interior_mutation(&self, important_locals: &ImportantLocals, tcx: TyCtxt<'tcx>) -> bool {
+        let compile = |mutators: &[&str]| {
+            mutators
+                .iter()
+                .map(|mutator| Regex::new(mutator).unwrap())
+                .collect()
+        };
+        let mut interior_mutation_visitor = InteriorMutationVisitor {
+            tcx,
+            body: self,
+            important_locals,
+            value_mutators: compile(&VALUE_MUTATORS),
+            guard_mutators: compile(&GUARD_MUTATORS),
+            local_origins: LocalOrigins::compute(self, tcx),
+            guards: HashSet::new(),
+            has_interior_mutation: false,
+        };
+        interior_mutation_visitor.collect_guards();
+        interior_mutation_visitor.visit_body(self);
+        interior_mutation_visitor.has_interior_mutation
+    }
+}
+
+impl<'a, 'tcx> InteriorMutationVisitor<'a, 'tcx> {
+    fn is_important(&self, local: Local) -> bool {
+        self.local_origins
+            .chain(local)
+            .iter()
+            .any(|local| self.important_locals.locals().contains(local))
+    }
+
+    // Writing to a cell only leaks if the cell outlives the function and did not already hold
+    // important data.
+    fn is_leaking_receiver(&self, receiver: &Operand<'tcx>) -> bool {
+        match receiver {
+            Operand::Constant(constant) => constant.check_static_ptr(self.tcx).is_some(),
+            Operand::Copy(place) | Operand::Move(place) => {
+                self.local_origins
+                    .is_externally_visible(place.local, self.body)
+                    && !self.is_important(place.local)
+            }
+        }
+    }
+
+    fn is_guard(&self, local: Local) -> bool {
+        self.local_origins
+            .chain(local)
+            .iter()
+            .any(|local| self.guards.contains(local))
+    }
+
+    // Find the guards returned for leaking receivers and everything derived from them through
+    // calls, e.g. `DerefMut::deref_mut` or `Result::unwrap`.
+    fn collect_guards(&mut self) {
+        loop {
+            let mut updated = false;
+            for block in self.body.basic_blocks.iter() {
+                if let Some(Terminator {
+                    kind:
+                        TerminatorKind::Call {
+                            func,
+                            args,
+                            destination,
+                            ..
+                        },
+                    ..
+                }) = &block.terminator
+                {
+                    let returns_guard = func
+                        .const_fn_def()
+                        .map(|(def_id, _)| {
+                            let path = readable_path(def_id, self.tcx);
+                            self.guard_mutators
+                                .iter()
+                                .any(|mutator| mutator.is_match(&path))
+                        })
+                        .unwrap_or(false)
+                        && args
+                            .first()
+                            .map(|receiver| self.is_leaking_receiver(receiver))
+                            .unwrap_or(false);
+                    let derives_guard = args.iter().any(|arg| {
+                        arg.place()
+                            .map(|place| self.is_guard(place.local))
+                            .unwrap_or(false)
+                    });
+                    if returns_guard || derives_guard {
+                        updated |= self.guards.insert(destination.local);
+                    }
+                }
+            }
+            if !updated {
+                break;
+            }
+        }
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for InteriorMutationVisitor<'a, 'tcx> {
+    fn visit_statement(&mut self, statement: &Statement<'tcx>, location: Location) {
+        if let StatementKind::Assign(box (place, rvalue)) = &statement.kind {
+            if place.is_indirect() && self.is_guard(place.local) {
+                let mut local_collector = LocalCollector::default();
+                local_collector.visit_rvalue(rvalue, location);
+                if local_collector
+                    .locals
+                    .iter()
+                    .any(|local| self.is_important(*local))
+                {
+                    self.has_interior_mutation = true;
+                }
+            }
+        }
+        self.super_statement(statement, location);
+    }
+
+    fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location) {
+        if let TerminatorKind::Call { func, args, .. } = &terminator.kind {
+            let passes_important = |args: &[Operand<'tcx>]| {
+                args.iter().any(|arg| {
+                    arg.place()
+                        .map(|place| self.is_important(place.local))
+                        .unwrap_or(false)
+                })
+            };
+
+            let is_value_mutator = func
+                .const_fn_def()
+                .map(|(def_id, _)| {
+                    let path = readable_path(def_id, self.tcx);
+                    self.value_mutators
+                        .iter()
+                        .any(|mutator| mutator.is_match(&path))
+                })
+                .unwrap_or(false);
+            // Important data stored into a cell, e.g. `CELL.set(a)`.
+            let stores_important = is_value_mutator
+                && args
+                    .first()
+                    .map(|receiver| self.is_leaking_receiver(receiver))
+                    .unwrap_or(false)
+                && passes_important(&args[1..]);
+
+            // Important data passed to a call on a guard, e.g. `MUTEX.lock().unwrap().push(a)`.
+            let writes_through_guard = args.iter().any(|arg| {
+                arg.place()
+                    .map(|place| self.is_guard(place.local))
+                    .unwrap_or(false)
+            }) && passes_important(args);
+
+            if stores_important || writes_through_guard {
+                self.has_interior_mutation = true;
+            }
+        }
+        self.super_terminator(terminator, location);
+    }
+}
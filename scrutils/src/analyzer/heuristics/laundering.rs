@@ -0,0 +1,240 @@
+use regex::Regex;
+use rustc_middle::mir::{
+    visit::{PlaceContext, Visitor},
+    Body, CastKind, Location, Mutability, Operand, Place, ProjectionElem, Rvalue, Terminator,
+    TerminatorKind,
+};
+use rustc_middle::ty::{self, Ty, TyCtxt, TypeSuperVisitable, TypeVisitable, TypeVisitor};
+use rustc_span::sym;
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashSet;
+use std::ops::ControlFlow;
+
+use crate::analyzer::heuristics::origins::LocalOrigins;
+use crate::common::readable_path;
+
+// `mem::transmute` is the `transmute` intrinsic re-exported, so it is recognized as such instead
+// of by its path, but `mem::transmute_copy` is a regular function.
+const TRANSMUTE_COPIES: &str = r"^core::mem::transmute_copy$";
+
+/// The way mutable access was manufactured from immutable data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunderingKind {
+    // A call to `mem::transmute`.
+    Transmute,
+    // A call to `mem::transmute_copy`.
+    TransmuteCopy,
+    // A `CastKind::Transmute` rvalue.
+    TransmuteCast,
+    // A cast of a `*const` pointer obtained from a shared reference into a `*mut` pointer.
+    ConstToMutCast,
+    // A read or borrow of a union field that holds mutable references or pointers.
+    UnionRead,
+}
+
+/// A single place in a body where mutability is laundered.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LaunderingSite {
+    kind: LaunderingKind,
+    location: String,
+    span: String,
+}
+
+struct MutabilityLaunderingVisitor<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'a Body<'tcx>,
+    transmute_copies: Regex,
+    local_origins: LocalOrigins,
+    sites: Vec<LaunderingSite>,
+}
+
+pub trait HasMutabilityLaundering<'tcx> {
+    fn mutability_laundering(&self, tcx: TyCtxt<'tcx>) -> Vec<LaunderingSite>;
+}
+
+impl<'tcx> HasMutabilityLaundering<'tcx> for Body<'tcx> {
+    fn mutability_laundering(&self, tcx: TyCtxt<'tcx>) -> Vec<LaunderingSite> {
+        let mut laundering_visitor = MutabilityLaunderingVisitor {
+            tcx,
+            body: self,
+            transmute_copies: Regex::new(TRANSMUTE_COPIES).unwrap(),
+            local_origins: LocalOrigins::compute(self, tcx),
+            sites: vec![],
+        };
+        laundering_visitor.visit_body(self);
+        laundering_visitor.sites
+    }
+}
+
+impl<'a, 'tcx> MutabilityLaunderingVisitor<'a, 'tcx> {
+    fn record(&mut self, kind: LaunderingKind, location: Location) {
+        let span = self.body.source_info(location).span;
+        self.sites.push(LaunderingSite {
+            kind,
+            location: format!("{:?}", location),
+            span: self.tcx.sess.source_map().span_to_diagnostic_string(span),
+        });
+    }
+
+    // Whether the pointer was created from a shared reference, e.g. `&x as *const T`. Casts of
+    // pointers that were mutable to begin with, like the ones in `NonNull::as_ptr` or
+    // `<*const T>::cast_mut`, are common and do not launder anything by themselves.
+    fn is_from_shared_ref(&self, operand: &Operand<'tcx>) -> bool {
+        operand
+            .place()
+            .map(|place| {
+                self.local_origins.chain(place.local).iter().any(|local| {
+                    matches!(
+                        self.body.local_decls[*local].ty.kind(),
+                        ty::TyKind::Ref(_, _, Mutability::Not)
+                    )
+                })
+            })
+            .unwrap_or(false)
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for MutabilityLaunderingVisitor<'a, 'tcx> {
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        if let Rvalue::Cast(cast_kind, operand, to_ty) = rvalue {
+            let from_ty = operand.ty(self.body, self.tcx);
+            if let CastKind::Transmute = cast_kind {
+                if launders_mutability(from_ty, *to_ty, self.tcx) {
+                    self.record(LaunderingKind::TransmuteCast, location);
+                }
+            } else if from_ty.is_unsafe_ptr()
+                && !from_ty.is_mutable_ptr()
+                && to_ty.is_mutable_ptr()
+                && self.is_from_shared_ref(operand)
+            {
+                self.record(LaunderingKind::ConstToMutCast, location);
+            }
+        }
+        self.super_rvalue(rvalue, location);
+    }
+
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
+        // Only unions that can also be written through a shared reference or pointer are
+        // considered, which excludes e.g. `MaybeUninit<&mut T>`.
+        let reads_mutable_union_field = place.iter_projections().any(|(place_ref, elem)| {
+            match (place_ref.ty(self.body, self.tcx).ty.kind(), elem) {
+                (ty::TyKind::Adt(adt_def, substs), ProjectionElem::Field(field_idx, field_ty))
+                    if adt_def.is_union() =>
+                {
+                    // Overwriting the field does not read it, but dereferencing or borrowing
+                    // it does, e.g. `(*(_1.1)) = _2`.
+                    let overwrites_field = context.is_mutating_use()
+                        && !context.is_borrow()
+                        && !context.is_address_of()
+                        && !place.projection[place_ref.projection.len() + 1..]
+                            .contains(&ProjectionElem::Deref);
+                    !overwrites_field
+                        && contains_mutable_access(&field_ty, self.tcx)
+                        && adt_def.non_enum_variant().fields.iter_enumerated().any(
+                            |(other_idx, other_field)| {
+                                other_idx != field_idx
+                                    && contains_shared_access(
+                                        &other_field.ty(self.tcx, substs),
+                                        self.tcx,
+                                    )
+                            },
+                        )
+                }
+                _ => false,
+            }
+        });
+        if reads_mutable_union_field {
+            self.record(LaunderingKind::UnionRead, location);
+        }
+        self.super_place(place, context, location);
+    }
+
+    fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location) {
+        if let TerminatorKind::Call { func, .. } = &terminator.kind {
+            if let Some((def_id, generic_args)) = func.const_fn_def() {
+                let kind = if self.tcx.is_intrinsic(def_id)
+                    && self.tcx.item_name(def_id) == sym::transmute
+                {
+                    Some(LaunderingKind::Transmute)
+                } else if self
+                    .transmute_copies
+                    .is_match(&readable_path(def_id, self.tcx))
+                {
+                    Some(LaunderingKind::TransmuteCopy)
+                } else {
+                    None
+                };
+                if let Some(kind) = kind {
+                    let from_ty = generic_args[0].as_type().unwrap();
+                    let to_ty = generic_args[1].as_type().unwrap();
+                    if launders_mutability(from_ty, to_ty, self.tcx) {
+                        self.record(kind, location);
+                    }
+                }
+            }
+        }
+        self.super_terminator(terminator, location);
+    }
+}
+
+// Reinterpreting a value launders mutability if the result grants mutable access, unless the
+// source already granted only mutable access.
+fn launders_mutability<'tcx>(from_ty: Ty<'tcx>, to_ty: Ty<'tcx>, tcx: TyCtxt<'tcx>) -> bool {
+    contains_mutable_access(&to_ty, tcx)
+        && (contains_shared_access(&from_ty, tcx) || !contains_mutable_access(&from_ty, tcx))
+}
+
+fn contains_mutable_access<'tcx>(ty: &Ty<'tcx>, tcx: TyCtxt<'tcx>) -> bool {
+    contains_access(ty, Mutability::Mut, tcx)
+}
+
+fn contains_shared_access<'tcx>(ty: &Ty<'tcx>, tcx: TyCtxt<'tcx>) -> bool {
+    contains_access(ty, Mutability::Not, tcx)
+}
+
+// Whether the type contains a reference or a raw pointer of the given mutability.
+fn contains_access<'tcx>(ty: &Ty<'tcx>, mutability: Mutability, tcx: TyCtxt<'tcx>) -> bool {
+    struct ContainsAccessVisitor<'tcx> {
+        tcx: TyCtxt<'tcx>,
+        mutability: Mutability,
+        visited: HashSet<Ty<'tcx>>,
+        has_access: bool,
+    }
+
+    impl<'tcx> TypeVisitor<TyCtxt<'tcx>> for ContainsAccessVisitor<'tcx> {
+        type BreakTy = ();
+
+        fn visit_ty(&mut self, t: Ty<'tcx>) -> ControlFlow<Self::BreakTy> {
+            // Recursive types would otherwise be unfolded forever.
+            if !self.visited.insert(t) {
+                return ControlFlow::Continue(());
+            }
+            if let ty::TyKind::Adt(adt_def, substs) = t.kind() {
+                for field in adt_def.all_fields() {
+                    field.ty(self.tcx, substs).visit_with(self)?;
+                }
+            }
+
+            let access_mutability = match t.kind() {
+                ty::TyKind::Ref(_, _, mutability) => Some(*mutability),
+                ty::TyKind::RawPtr(type_and_mut) => Some(type_and_mut.mutbl),
+                _ => None,
+            };
+            if access_mutability == Some(self.mutability) {
+                self.has_access = true;
+            }
+            t.super_visit_with(self)
+        }
+    }
+
+    let mut visitor = ContainsAccessVisitor {
+        tcx,
+        mutability,
+        visited: HashSet::new(),
+        has_access: false,
+    };
+    ty.visit_with(&mut visitor);
+    visitor.has_access
+}
@@ -1,5 +1,12 @@
+mod foreign;
+mod global_state;
+mod interior_mut;
+mod laundering;
+mod origins;
 mod raw_ptr;
-mod transmute;
 
-pub use raw_ptr::HasRawPtrDeref;
-pub use transmute::HasTransmute;
+pub use foreign::{check_foreign_call, FfiSummary};
+pub use global_state::HasGlobalStateWrite;
+pub use interior_mut::HasInteriorMutation;
+pub use laundering::{HasMutabilityLaundering, LaunderingSite};
+pub use raw_ptr::{HasLeakingRawPtrWrite, HasRawPtrDeref, RawPtrHeuristic};
@@ -0,0 +1,136 @@
+use rustc_hir::def_id::DefId;
+use rustc_middle::mir::{
+    visit::{PlaceContext, Visitor},
+    Body, Constant, Local, Location, Operand, Rvalue, Statement, StatementKind,
+};
+use rustc_middle::ty::{self, TyCtxt};
+use std::collections::{HashMap, HashSet};
+
+use crate::common::readable_path;
+
+// The type of the keys declared with `thread_local!`.
+const LOCAL_KEY: &str = "std::thread::local::LocalKey";
+
+// The local every reference, pointer or copy was created from, and the statics, thread locals and
+// `thread_local!` keys that locals point to.
+pub struct LocalOrigins {
+    origins: HashMap<Local, Local>,
+    statics: HashMap<Local, DefId>,
+    thread_locals: HashSet<Local>,
+    local_keys: HashSet<Local>,
+}
+
+// Whether the constant is a `thread_local!` key or a reference to one. Keys are `const` items, so
+// they are not reached through statics.
+pub fn is_local_key_constant<'tcx>(constant: &Constant<'tcx>, tcx: TyCtxt<'tcx>) -> bool {
+    match constant.ty().peel_refs().kind() {
+        ty::TyKind::Adt(adt_def, _) => readable_path(adt_def.did(), tcx) == LOCAL_KEY,
+        _ => false,
+    }
+}
+
+impl LocalOrigins {
+    pub fn compute<'tcx>(body: &Body<'tcx>, tcx: TyCtxt<'tcx>) -> Self {
+        let mut origin_visitor = OriginVisitor {
+            tcx,
+            local_origins: LocalOrigins {
+                origins: HashMap::new(),
+                statics: HashMap::new(),
+                thread_locals: HashSet::new(),
+                local_keys: HashSet::new(),
+            },
+        };
+        origin_visitor.visit_body(body);
+        origin_visitor.local_origins
+    }
+
+    // The local itself and all locals it was transitively created from.
+    pub fn chain(&self, local: Local) -> Vec<Local> {
+        let mut chain = vec![local];
+        let mut current = local;
+        while let Some(origin) = self.origins.get(&current) {
+            if chain.contains(origin) {
+                break;
+            }
+            chain.push(*origin);
+            current = *origin;
+        }
+        chain
+    }
+
+    pub fn static_of(&self, local: Local) -> Option<DefId> {
+        self.chain(local)
+            .iter()
+            .find_map(|local| self.statics.get(local).copied())
+    }
+
+    pub fn is_thread_local(&self, local: Local) -> bool {
+        self.chain(local)
+            .iter()
+            .any(|local| self.thread_locals.contains(local))
+    }
+
+    pub fn is_local_key(&self, local: Local) -> bool {
+        self.chain(local)
+            .iter()
+            .any(|local| self.local_keys.contains(local))
+    }
+
+    // Whether the local is derived from an argument or a static, and hence visible outside of the
+    // function.
+    pub fn is_externally_visible(&self, local: Local, body: &Body) -> bool {
+        self.static_of(local).is_some()
+            || self.is_thread_local(local)
+            || self
+                .chain(local)
+                .iter()
+                .any(|local| (1..=body.arg_count).contains(&local.as_usize()))
+    }
+}
+
+struct OriginVisitor<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    local_origins: LocalOrigins,
+}
+
+impl<'tcx> Visitor<'tcx> for OriginVisitor<'tcx> {
+    fn visit_statement(&mut self, statement: &Statement<'tcx>, location: Location) {
+        if let StatementKind::Assign(box (place, rvalue)) = &statement.kind {
+            if let Some(local) = place.as_local() {
+                match rvalue {
+                    Rvalue::Ref(_, _, origin)
+                    | Rvalue::AddressOf(_, origin)
+                    | Rvalue::CopyForDeref(origin)
+                    | Rvalue::Use(Operand::Copy(origin) | Operand::Move(origin))
+                    | Rvalue::Cast(_, Operand::Copy(origin) | Operand::Move(origin), _) => {
+                        self.local_origins.origins.insert(local, origin.local);
+                    }
+                    Rvalue::Use(Operand::Constant(constant)) => {
+                        if let Some(def_id) = constant.check_static_ptr(self.tcx) {
+                            self.local_origins.statics.insert(local, def_id);
+                        } else if is_local_key_constant(constant, self.tcx) {
+                            self.local_origins.local_keys.insert(local);
+                        }
+                    }
+                    Rvalue::ThreadLocalRef(..) => {
+                        self.local_origins.thread_locals.insert(local);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        self.super_statement(statement, location);
+    }
+}
+
+// All locals mentioned in the visited part of a body.
+#[derive(Default)]
+pub struct LocalCollector {
+    pub locals: HashSet<Local>,
+}
+
+impl<'tcx> Visitor<'tcx> for LocalCollector {
+    fn visit_local(&mut self, local: Local, _context: PlaceContext, _location: Location) {
+        self.locals.insert(local);
+    }
+}
@@ -1,8 +1,29 @@
+use flowistry::infoflow::Direction;
+use itertools::Itertools;
+use rustc_hir::def_id::DefId;
 use rustc_middle::mir::{
-    visit::Visitor, Body, Location, Mutability, Place, ProjectionElem, Rvalue, Statement,
+    visit::Visitor, Body, Local, Location, Mutability, Place, ProjectionElem, Rvalue, Statement,
     StatementKind,
 };
 use rustc_middle::ty::TyCtxt;
+use rustc_utils::mir::location_or_arg::LocationOrArg;
+use rustc_utils::PlaceExt;
+use serde::{Deserialize, Serialize};
+
+use crate::analyzer::heuristics::origins::{LocalCollector, LocalOrigins};
+use crate::body_cache::is_mir_available;
+use crate::important::{compute_dependent_locals_per_target, ImportantLocals};
+
+/// How writes through raw pointers are checked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RawPtrHeuristic {
+    /// Any write through a `*mut` deref fails the function.
+    Coarse,
+    /// Only writes of important data through pointers that may alias memory visible outside of
+    /// the function fail it.
+    Dataflow,
+}
 
 struct RawPtrDerefVisitor<'a, 'tcx> {
     tcx: TyCtxt<'tcx>,
@@ -76,3 +97,136 @@ impl<'a, 'tcx> Visitor<'tcx> for RawPtrDerefVisitor<'a, 'tcx> {
         self.super_statement(statement, location);
     }
 }
+
+// A write through a raw pointer, or a mutable reborrow of one.
+struct RawPtrWrite {
+    location: Location,
+    pointer: Local,
+    is_reborrow: bool,
+    writes_important: bool,
+}
+
+struct RawPtrWriteCollector<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'a Body<'tcx>,
+    important_locals: &'a ImportantLocals,
+    writes: Vec<RawPtrWrite>,
+}
+
+pub trait HasLeakingRawPtrWrite<'tcx> {
+    fn has_leaking_raw_ptr_write(
+        &self,
+        def_id: DefId,
+        important_locals: &ImportantLocals,
+        tcx: TyCtxt<'tcx>,
+    ) -> bool;
+}
+
+impl<'tcx> HasLeakingRawPtrWrite<'tcx> for Body<'tcx> {
+    fn has_leaking_raw_ptr_write(
+        &self,
+        def_id: DefId,
+        important_locals: &ImportantLocals,
+        tcx: TyCtxt<'tcx>,
+    ) -> bool {
+        // Flowistry needs the body with borrowck facts, fall back to the coarse check without it.
+        if !is_mir_available(def_id, tcx) {
+            return self.has_raw_ptr_deref(tcx);
+        }
+
+        let mut write_collector = RawPtrWriteCollector {
+            tcx,
+            body: self,
+            important_locals,
+            writes: vec![],
+        };
+        write_collector.visit_body(self);
+
+        // Writes of unimportant data cannot leak anything. Reborrows are kept, as the important
+        // data can be written through the resulting reference later on.
+        let candidates = write_collector
+            .writes
+            .into_iter()
+            .filter(|write| write.is_reborrow || write.writes_important)
+            .collect_vec();
+        if candidates.is_empty() {
+            return false;
+        }
+
+        // Compute where each pointer could have come from.
+        let targets = candidates
+            .iter()
+            .map(|write| {
+                vec![(
+                    Place::make(write.pointer, &[], tcx),
+                    LocationOrArg::Location(write.location),
+                )]
+            })
+            .collect_vec();
+        let pointer_origins =
+            compute_dependent_locals_per_target(tcx, def_id, targets, Direction::Backward);
+
+        let local_origins = LocalOrigins::compute(self, tcx);
+        pointer_origins.iter().any(|origins| {
+            origins
+                .iter()
+                .any(|origin| aliases_outside(*origin, &local_origins, self))
+        })
+    }
+}
+
+// Whether the local may point to memory that is reachable from outside of the function, but which
+// the callers do not expect to be modified. Memory behind mutable arguments is not included, as
+// the callers already account for it being modified.
+fn aliases_outside(local: Local, local_origins: &LocalOrigins, body: &Body) -> bool {
+    let is_immutable_arg = |local: &Local| {
+        if !(1..=body.arg_count).contains(&local.as_usize()) {
+            return false;
+        }
+        let ty = body.local_decls[*local].ty;
+        ty.ref_mutability() == Some(Mutability::Not) || (ty.is_unsafe_ptr() && !ty.is_mutable_ptr())
+    };
+    local_origins.static_of(local).is_some()
+        || local_origins.is_thread_local(local)
+        || local_origins.chain(local).iter().any(is_immutable_arg)
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for RawPtrWriteCollector<'a, 'tcx> {
+    fn visit_statement(&mut self, statement: &Statement<'tcx>, location: Location) {
+        if let StatementKind::Assign(box (place, rvalue)) = &statement.kind {
+            let place_ext = PlaceWithBody {
+                place,
+                body: self.body,
+            };
+            if place_ext.has_raw_ptr_deref(self.tcx) {
+                let mut local_collector = LocalCollector::default();
+                local_collector.visit_rvalue(rvalue, location);
+                self.writes.push(RawPtrWrite {
+                    location,
+                    pointer: place.local,
+                    is_reborrow: false,
+                    writes_important: local_collector
+                        .locals
+                        .iter()
+                        .any(|local| self.important_locals.locals().contains(local)),
+                });
+            } else if let Rvalue::Ref(_, borrow_kind, borrow_place) = rvalue {
+                let borrow_place_ext = PlaceWithBody {
+                    place: borrow_place,
+                    body: self.body,
+                };
+                if let Mutability::Mut = borrow_kind.mutability() {
+                    if borrow_place_ext.has_raw_ptr_deref(self.tcx) {
+                        self.writes.push(RawPtrWrite {
+                            location,
+                            pointer: borrow_place.local,
+                            is_reborrow: true,
+                            writes_important: false,
+                        });
+                    }
+                }
+            }
+        }
+        self.super_statement(statement, location);
+    }
+}
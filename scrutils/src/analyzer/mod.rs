@@ -1,7 +1,11 @@
 mod analyzer;
+mod cache;
 mod deps;
 mod heuristics;
 mod result;
+mod summary;
 
 pub use analyzer::run;
-pub use result::PurityAnalysisResult;
+pub use cache::VerdictCache;
+pub use heuristics::{FfiSummary, RawPtrHeuristic};
+pub use result::{FunctionWithMetadata, PurityAnalysisResult};
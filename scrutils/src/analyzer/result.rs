@@ -1,20 +1,25 @@
 use std::collections::HashSet;
 
 use rustc_hir::def_id::DefId;
+use rustc_middle::ty;
 use serde::{ser::SerializeStruct, Serialize};
 
 use crate::analyzer::deps::compute_dep_strings_for_crates;
+use crate::analyzer::heuristics::LaunderingSite;
 use crate::common::storage::ClosureInfoStorage;
-use crate::common::FunctionInfo;
+use crate::common::{FailureReason, FunctionIdentifier, FunctionInfo};
 use crate::important::ImportantLocals;
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct FunctionWithMetadata<'tcx> {
     function: FunctionInfo<'tcx>,
     important_locals: ImportantLocals,
     raw_pointer_deref: bool,
     allowlisted: bool,
-    has_transmute: bool,
+    mutability_laundering: Vec<LaunderingSite>,
+    has_interior_mutation: bool,
+    global_state_write: bool,
+    failure_reasons: Vec<FailureReason>,
 }
 
 impl<'tcx> FunctionWithMetadata<'tcx> {
@@ -23,42 +28,133 @@ impl<'tcx> FunctionWithMetadata<'tcx> {
         important_locals: ImportantLocals,
         raw_pointer_deref: bool,
         allowlisted: bool,
-        has_transmute: bool,
+        mutability_laundering: Vec<LaunderingSite>,
+        has_interior_mutation: bool,
+        global_state_write: bool,
+        failure_reasons: Vec<FailureReason>,
     ) -> Self {
         FunctionWithMetadata {
             function,
             important_locals,
             raw_pointer_deref,
             allowlisted,
-            has_transmute,
+            mutability_laundering,
+            has_interior_mutation,
+            global_state_write,
+            failure_reasons,
         }
     }
+
+    pub fn function(&self) -> &FunctionInfo<'tcx> {
+        &self.function
+    }
+
+    pub fn important_locals(&self) -> &ImportantLocals {
+        &self.important_locals
+    }
+
+    pub fn raw_pointer_deref(&self) -> bool {
+        self.raw_pointer_deref
+    }
+
+    pub fn allowlisted(&self) -> bool {
+        self.allowlisted
+    }
+
+    // Places in the body where mutable access is obtained from immutable data.
+    pub fn mutability_laundering(&self) -> &Vec<LaunderingSite> {
+        &self.mutability_laundering
+    }
+
+    pub fn has_mutability_laundering(&self) -> bool {
+        !self.mutability_laundering.is_empty()
+    }
+
+    pub fn has_interior_mutation(&self) -> bool {
+        self.has_interior_mutation
+    }
+
+    pub fn global_state_write(&self) -> bool {
+        self.global_state_write
+    }
+
+    // Reasons why the function itself failed; empty for passing functions.
+    pub fn failure_reasons(&self) -> &Vec<FailureReason> {
+        &self.failure_reasons
+    }
+
+    pub fn add_failure_reason(&mut self, reason: FailureReason) {
+        self.failure_reasons.push(reason);
+    }
+}
+
+// A single call on the path from a region to the function that made it fail, together with the
+// argument locals that carried important data into the call.
+#[derive(Clone)]
+pub struct WitnessHop<'tcx> {
+    function: FunctionWithMetadata<'tcx>,
+    important_args: Vec<usize>,
+}
+
+impl<'tcx> WitnessHop<'tcx> {
+    pub fn new(function: FunctionWithMetadata<'tcx>, important_args: Vec<usize>) -> Self {
+        WitnessHop {
+            function,
+            important_args,
+        }
+    }
+
+    pub fn function(&self) -> &FunctionWithMetadata<'tcx> {
+        &self.function
+    }
+
+    pub fn important_args(&self) -> &Vec<usize> {
+        &self.important_args
+    }
+}
+
+impl<'tcx> Serialize for WitnessHop<'tcx> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut state = serializer.serialize_struct("WitnessHop", 5)?;
+        let def_id = self.function.function().def_id();
+        state.serialize_field("def_id", format!("{:?}", def_id).as_str())?;
+        state.serialize_field("name", &FunctionIdentifier::current(def_id))?;
+        state.serialize_field("important_args", &self.important_args)?;
+        state.serialize_field("has_body", &self.function.function().instance().is_some())?;
+        state.serialize_field("failure_reasons", self.function.failure_reasons())?;
+        state.end()
+    }
 }
 
 pub struct PurityAnalysisResult<'tcx> {
-    def_id: DefId,
+    instance: ty::Instance<'tcx>,
     annotated_pure: bool,
     status: bool,
-    reason: String,
+    reason: Option<FailureReason>,
     passing: Vec<FunctionWithMetadata<'tcx>>,
     failing: Vec<FunctionWithMetadata<'tcx>>,
     closures: ClosureInfoStorage<'tcx>,
     deps: HashSet<String>,
+    witness: Vec<WitnessHop<'tcx>>,
 }
 
 impl<'tcx> PurityAnalysisResult<'tcx> {
     pub fn new(
-        def_id: DefId,
+        instance: ty::Instance<'tcx>,
         annotated_pure: bool,
         status: bool,
-        reason: String,
+        reason: Option<FailureReason>,
         passing: Vec<FunctionWithMetadata<'tcx>>,
         failing: Vec<FunctionWithMetadata<'tcx>>,
         closures: ClosureInfoStorage<'tcx>,
         deps: HashSet<String>,
+        witness: Vec<WitnessHop<'tcx>>,
     ) -> Self {
         Self {
-            def_id,
+            instance,
             annotated_pure,
             status,
             reason,
@@ -66,19 +162,25 @@ impl<'tcx> PurityAnalysisResult<'tcx> {
             failing,
             closures,
             deps,
+            witness,
         }
     }
 
-    pub fn error(def_id: DefId, reason: String, annotated_pure: bool) -> Self {
+    pub fn error(
+        instance: ty::Instance<'tcx>,
+        reason: FailureReason,
+        annotated_pure: bool,
+    ) -> Self {
         Self::new(
-            def_id,
+            instance,
             annotated_pure,
             false,
-            reason,
+            Some(reason),
             vec![],
             vec![],
             ClosureInfoStorage::new(),
             HashSet::new(),
+            vec![],
         )
     }
 
@@ -86,8 +188,41 @@ impl<'tcx> PurityAnalysisResult<'tcx> {
         self.annotated_pure != self.status
     }
 
-    pub fn def_id(&self) -> &DefId {
-        &self.def_id
+    pub fn def_id(&self) -> DefId {
+        self.instance.def_id()
+    }
+
+    pub fn instance(&self) -> &ty::Instance<'tcx> {
+        &self.instance
+    }
+
+    pub fn annotated_pure(&self) -> bool {
+        self.annotated_pure
+    }
+
+    pub fn status(&self) -> bool {
+        self.status
+    }
+
+    pub fn reason(&self) -> Option<&FailureReason> {
+        self.reason.as_ref()
+    }
+
+    pub fn passing(&self) -> &Vec<FunctionWithMetadata<'tcx>> {
+        &self.passing
+    }
+
+    pub fn failing(&self) -> &Vec<FunctionWithMetadata<'tcx>> {
+        &self.failing
+    }
+
+    pub fn witness(&self) -> &Vec<WitnessHop<'tcx>> {
+        &self.witness
+    }
+
+    // Functions on the path from the region down to the first function that failed on its own.
+    pub fn failure_chain(&self) -> Vec<&FunctionWithMetadata<'tcx>> {
+        self.witness.iter().map(|hop| hop.function()).collect()
     }
 }
 
@@ -96,12 +231,15 @@ impl<'tcx> Serialize for PurityAnalysisResult<'tcx> {
     where
         S: serde::Serializer,
     {
-        let mut state = serializer.serialize_struct("PurityAnalysisResult", 8)?;
-        state.serialize_field("def_id", format!("{:?}", self.def_id).as_str())?;
+        let mut state = serializer.serialize_struct("PurityAnalysisResult", 11)?;
+        state.serialize_field("def_id", format!("{:?}", self.def_id()).as_str())?;
+        state.serialize_field("name", &FunctionIdentifier::current(self.def_id()))?;
+        state.serialize_field("instance", format!("{}", self.instance).as_str())?;
         state.serialize_field("annotated_pure", &self.annotated_pure)?;
         state.serialize_field("status", &self.status)?;
         if !self.status {
             state.serialize_field("reason", &self.reason)?;
+            state.serialize_field("witness", &self.witness)?;
         }
         state.serialize_field("passing", &self.passing)?;
         state.serialize_field("failing", &self.failing)?;
@@ -0,0 +1,299 @@
+use itertools::Itertools;
+use log::{trace, warn};
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_middle::mir::{Local, Place};
+use rustc_middle::ty::TyCtxt;
+use rustc_utils::PlaceExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::iter::once;
+
+use crate::analyzer::cache::LocalVerdict;
+use crate::analyzer::heuristics::LaunderingSite;
+use crate::analyzer::result::FunctionWithMetadata;
+use crate::body_cache::{intermediate_out_dir, local_or_remote_paths};
+use crate::common::{fnv1a, readable_path, FailureReason, FunctionInfo, NormalizedPlace};
+use crate::important::ImportantLocals;
+
+const SUMMARY_ARTIFACT_EXT: &str = "fsum";
+
+/// The persisted part of a verdict: the analysis of the function itself, without the verdicts of
+/// its callees, which are always recomputed.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FunctionSummary {
+    important_locals: Vec<usize>,
+    raw_pointer_deref: bool,
+    allowlisted: bool,
+    mutability_laundering: Vec<LaunderingSite>,
+    has_interior_mutation: bool,
+    global_state_write: bool,
+    failure_reasons: Vec<FailureReason>,
+    follows_calls: bool,
+    calls: Vec<String>,
+    collected: String,
+    deps: HashSet<String>,
+}
+
+impl FunctionSummary {
+    fn from_verdict<'tcx>(verdict: &LocalVerdict<'tcx>, tcx: TyCtxt<'tcx>) -> Self {
+        let function_with_metadata = verdict.function_with_metadata();
+        FunctionSummary {
+            important_locals: function_with_metadata
+                .important_locals()
+                .locals()
+                .iter()
+                .map(|local| local.as_usize())
+                .sorted()
+                .collect(),
+            raw_pointer_deref: function_with_metadata.raw_pointer_deref(),
+            allowlisted: function_with_metadata.allowlisted(),
+            mutability_laundering: function_with_metadata.mutability_laundering().to_owned(),
+            has_interior_mutation: function_with_metadata.has_interior_mutation(),
+            global_state_write: function_with_metadata.global_state_write(),
+            failure_reasons: function_with_metadata.failure_reasons().to_owned(),
+            follows_calls: verdict.follows_calls(),
+            calls: summarize_calls(function_with_metadata.function(), tcx),
+            collected: summarize_collected(function_with_metadata.function()),
+            deps: verdict.deps().to_owned(),
+        }
+    }
+
+    // A summary only applies if the function was collected the same way as when it was produced.
+    fn matches<'tcx>(&self, item: &FunctionInfo<'tcx>, tcx: TyCtxt<'tcx>) -> bool {
+        self.calls == summarize_calls(item, tcx) && self.collected == summarize_collected(item)
+    }
+
+    fn to_verdict<'tcx>(&self, item: &FunctionInfo<'tcx>) -> LocalVerdict<'tcx> {
+        let function_with_metadata = FunctionWithMetadata::new(
+            item.to_owned(),
+            ImportantLocals::from_locals(HashSet::from_iter(
+                self.important_locals
+                    .iter()
+                    .map(|local| Local::from_usize(*local)),
+            )),
+            self.raw_pointer_deref,
+            self.allowlisted,
+            self.mutability_laundering.clone(),
+            self.has_interior_mutation,
+            self.global_state_write,
+            self.failure_reasons.clone(),
+        );
+        LocalVerdict::new(
+            function_with_metadata,
+            self.deps.clone(),
+            self.follows_calls,
+        )
+    }
+}
+
+// Calls made by the function, by path, generic arguments and argument operands, all of which are
+// stable across sessions.
+fn summarize_calls<'tcx>(item: &FunctionInfo<'tcx>, tcx: TyCtxt<'tcx>) -> Vec<String> {
+    item.calls()
+        .map(|calls| {
+            calls
+                .iter()
+                .map(|call| {
+                    let generic_args = call
+                        .instance()
+                        .map(|instance| format!("{:?}", instance.args))
+                        .unwrap_or_default();
+                    format!(
+                        "{}{}({})",
+                        readable_path(call.def_id(), tcx),
+                        generic_args,
+                        call.args()
+                            .iter()
+                            .map(|arg| format!("{:?}", arg))
+                            .join(", ")
+                    )
+                })
+                .sorted()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Hash of the types tracked for every place, the unhandled types and the inline assembly blocks.
+fn summarize_collected(item: &FunctionInfo) -> String {
+    let collected = match item {
+        FunctionInfo::WithBody {
+            places,
+            unhandled,
+            inline_asm,
+            ..
+        } => {
+            let places = places
+                .iter()
+                .map(|(place, tracked_ty)| {
+                    let tys = tracked_ty
+                        .into_vec()
+                        .iter()
+                        .map(|ty| format!("{:?}", ty))
+                        .sorted()
+                        .join("|");
+                    format!("{:?}: {}", place, tys)
+                })
+                .sorted()
+                .join(";");
+            let unhandled = unhandled
+                .iter()
+                .map(|ty| format!("{:?}", ty))
+                .sorted()
+                .join(";");
+            format!("{}#{}#{}", places, unhandled, inline_asm.len())
+        }
+        FunctionInfo::WithoutBody { tracked_args, .. } => format!("{:?}", tracked_args),
+    };
+    format!("{:016x}", fnv1a(collected.as_bytes()))
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct SummaryFile {
+    fingerprint: String,
+    summaries: HashMap<String, FunctionSummary>,
+}
+
+/// Function summaries persisted next to the `.bwbf` artifacts of each crate.
+///
+/// Summaries only save the analysis of function bodies, i.e. the important locals dataflow and
+/// the heuristics, which dominate the analysis time. They do not save the collection of the call
+/// graph, which still runs for every region and is what the summaries are validated against.
+///
+/// Summaries are only produced by crates analyzed as the primary package and written to their
+/// artifacts. They are loaded from the local crate and every upstream crate, so results are reused
+/// across runs and by crates depending on previously analyzed ones. Dependencies that are only
+/// compiled and dumped, such as the standard library, get no summaries of their own; their
+/// functions are summarized under the analyzed crate once reached from it. Only summaries produced
+/// with the same `fingerprint` (i.e. the same analyzer version and configuration) are considered.
+pub struct SummaryStore {
+    fingerprint: String,
+    loaded: HashMap<String, FunctionSummary>,
+    produced: HashMap<String, FunctionSummary>,
+}
+
+impl SummaryStore {
+    pub fn load(fingerprint: String, tcx: TyCtxt) -> Self {
+        let loaded = tcx
+            .crates(())
+            .iter()
+            .chain(once(&LOCAL_CRATE))
+            .flat_map(|krate| local_or_remote_paths(*krate, tcx, SUMMARY_ARTIFACT_EXT))
+            .filter_map(|path| fs::read_to_string(&path).ok().map(|data| (path, data)))
+            .filter_map(
+                |(path, data)| match serde_json::from_str::<SummaryFile>(&data) {
+                    Ok(file) if file.fingerprint == fingerprint => Some(file.summaries),
+                    Ok(..) => {
+                        trace!("skipping summaries from {:?}: fingerprint mismatch", path);
+                        None
+                    }
+                    Err(err) => {
+                        warn!("failed to read summaries from {:?}: {}", path, err);
+                        None
+                    }
+                },
+            )
+            .flatten()
+            .collect();
+        SummaryStore {
+            fingerprint,
+            loaded,
+            produced: HashMap::new(),
+        }
+    }
+
+    pub fn get<'tcx>(
+        &self,
+        item: &FunctionInfo<'tcx>,
+        important_args: &Vec<usize>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Option<LocalVerdict<'tcx>> {
+        let key = summary_key(item, important_args, tcx)?;
+        self.produced
+            .get(&key)
+            .or_else(|| self.loaded.get(&key))
+            .filter(|summary| summary.matches(item, tcx))
+            .map(|summary| summary.to_verdict(item))
+    }
+
+    pub fn insert<'tcx>(
+        &mut self,
+        item: &FunctionInfo<'tcx>,
+        important_args: &Vec<usize>,
+        verdict: &LocalVerdict<'tcx>,
+        tcx: TyCtxt<'tcx>,
+    ) {
+        if let Some(key) = summary_key(item, important_args, tcx) {
+            self.produced
+                .insert(key, FunctionSummary::from_verdict(verdict, tcx));
+        }
+    }
+
+    /// Write all summaries produced during this run into the artifact of the local crate,
+    /// preserving the ones from previous runs.
+    pub fn persist(&self, tcx: TyCtxt) {
+        let path = intermediate_out_dir(tcx, SUMMARY_ARTIFACT_EXT);
+        let previous = fs::read_to_string(&path)
+            .ok()
+            .and_then(|data| serde_json::from_str::<SummaryFile>(&data).ok())
+            .filter(|file| file.fingerprint == self.fingerprint)
+            .map(|file| file.summaries)
+            .unwrap_or_default();
+        let file = SummaryFile {
+            fingerprint: self.fingerprint.clone(),
+            summaries: previous
+                .into_iter()
+                .chain(self.produced.clone().into_iter())
+                .collect(),
+        };
+        if let Err(err) = fs::write(&path, serde_json::to_string(&file).unwrap()) {
+            warn!("failed to write summaries to {:?}: {}", path, err);
+        }
+    }
+}
+
+// Build a key that identifies a verdict across crates and runs: the hash of the crate defining
+// the function (which changes whenever the crate or its dependencies change), its stable def path
+// hash, generic arguments, important argument positions and tracked argument types.
+fn summary_key<'tcx>(
+    item: &FunctionInfo<'tcx>,
+    important_args: &Vec<usize>,
+    tcx: TyCtxt<'tcx>,
+) -> Option<String> {
+    let (instance, places, body) = match item {
+        FunctionInfo::WithBody {
+            instance,
+            places,
+            body,
+            ..
+        } => (instance, places, body),
+        FunctionInfo::WithoutBody { .. } => return None,
+    };
+    let def_id = instance.def_id();
+    let tracked_args = (1..=body.arg_count)
+        .map(|arg| {
+            let arg_place = Place::make(Local::from_usize(arg), &[], tcx);
+            places
+                .get(&NormalizedPlace::from_place(&arg_place, tcx, def_id))
+                .map(|tracked_ty| {
+                    tracked_ty
+                        .into_vec()
+                        .iter()
+                        .map(|ty| format!("{:?}", ty))
+                        .sorted()
+                        .join("|")
+                })
+                .unwrap_or_default()
+        })
+        .collect_vec();
+    let important_args = important_args.iter().sorted().dedup().collect_vec();
+    Some(format!(
+        "{}:{}:{:?}:{:?}:{:?}",
+        tcx.crate_hash(def_id.krate).to_hex(),
+        tcx.def_path_hash(def_id).0.to_hex(),
+        instance.args,
+        important_args,
+        tracked_args
+    ))
+}
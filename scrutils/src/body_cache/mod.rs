@@ -1,7 +1,10 @@
 mod body_cache;
 mod encoder;
 
-pub use body_cache::{dump_mir_and_borrowck_facts, load_body_and_facts, BodyCache};
+pub use body_cache::{
+    dump_mir_and_borrowck_facts, intermediate_out_dir, load_body_and_facts, local_or_remote_paths,
+    BodyCache,
+};
 
 use rustc_hir::{def::DefKind, def_id::DefId};
 use rustc_middle::{
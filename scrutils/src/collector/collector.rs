@@ -1,11 +1,12 @@
 use itertools::Itertools;
 use log::{trace, warn};
 use rustc_middle::mir::{
-    BasicBlock, Body, CallReturnPlaces, Location, Mutability, Operand, Place, Statement,
-    StatementKind, Terminator, TerminatorEdges, TerminatorKind,
+    AggregateKind, BasicBlock, Body, CallReturnPlaces, Location, Mutability, Operand, Place,
+    Rvalue, Statement, StatementKind, Terminator, TerminatorEdges, TerminatorKind,
 };
 use rustc_middle::ty::{self, Ty, TyCtxt};
 use rustc_mir_dataflow::{Analysis, AnalysisDomain, JoinSemiLattice};
+use rustc_span::def_id::DefId;
 use rustc_utils::BodyExt;
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -22,6 +23,24 @@ use crate::common::storage::{
 };
 use crate::common::{ArgTys, FunctionCall, FunctionInfo, NormalizedPlace, TrackedTy};
 
+// Whether a call on a trait object could not be resolved to a concrete implementation.
+fn is_erased_dyn_call<'tcx>(
+    def_id: DefId,
+    substs: ty::GenericArgsRef<'tcx>,
+    fns: &Vec<PartialFunctionInfo<'tcx>>,
+    tcx: TyCtxt<'tcx>,
+) -> bool {
+    tcx.trait_of_item(def_id).is_some()
+        && substs
+            .types()
+            .next()
+            .map(|self_ty| self_ty.is_trait())
+            .unwrap_or(false)
+        && fns
+            .iter()
+            .all(|fn_data| matches!(fn_data.instance().def, ty::InstanceDef::Virtual(..)))
+}
+
 #[derive(Clone)]
 pub struct Collector<'tcx> {
     virtual_stack: VirtualStack<'tcx>,
@@ -30,6 +49,7 @@ pub struct Collector<'tcx> {
     function_storage_ref: FunctionInfoStorageRef<'tcx>,
     closure_storage_ref: ClosureInfoStorageRef<'tcx>,
     shallow: bool,
+    enumerate_implementors: bool,
     tcx: TyCtxt<'tcx>,
 }
 
@@ -44,7 +64,12 @@ impl<'tcx> Collector<'tcx> {
 }
 
 impl<'tcx> Collector<'tcx> {
-    pub fn collect(instance: ty::Instance<'tcx>, tcx: TyCtxt<'tcx>, shallow: bool) -> Self {
+    pub fn collect(
+        instance: ty::Instance<'tcx>,
+        tcx: TyCtxt<'tcx>,
+        shallow: bool,
+        enumerate_implementors: bool,
+    ) -> Self {
         let body = substituted_mir(&instance, tcx);
         let arg_tys = (1..=body.arg_count)
             .map(|local| {
@@ -63,6 +88,7 @@ impl<'tcx> Collector<'tcx> {
             function_storage_ref,
             closure_storage_ref,
             shallow,
+            enumerate_implementors,
             tcx,
         );
         let results = collector.run();
@@ -74,6 +100,7 @@ impl<'tcx> Collector<'tcx> {
             body.to_owned(),
             body.span,
             results.unhandled().to_owned(),
+            results.inline_asm().to_owned(),
         );
 
         collector
@@ -136,6 +163,7 @@ impl<'tcx> Collector<'tcx> {
         function_storage_ref: FunctionInfoStorageRef<'tcx>,
         closure_storage_ref: ClosureInfoStorageRef<'tcx>,
         shallow: bool,
+        enumerate_implementors: bool,
         tcx: TyCtxt<'tcx>,
     ) -> Self {
         let substituted_body = substituted_mir(current_function.instance(), tcx);
@@ -146,10 +174,51 @@ impl<'tcx> Collector<'tcx> {
             function_storage_ref,
             closure_storage_ref,
             shallow,
+            enumerate_implementors,
             tcx,
         }
     }
 
+    // Resolve a call through a function pointer to every function definition that flows into it.
+    fn process_fn_ptr_call(
+        &self,
+        func: &Operand<'tcx>,
+        args: &Vec<Operand<'tcx>>,
+        arg_tys: ArgTys<'tcx>,
+        state: &mut CollectorDomain<'tcx>,
+        destination: Option<&Place<'tcx>>,
+    ) {
+        let fn_ptr_ty = func.ty(&self.substituted_body, self.tcx);
+        let candidates = func
+            .tracked_ty(
+                state,
+                self.closure_storage_ref.clone(),
+                self.current_function.instance(),
+                self.tcx,
+            )
+            .into_vec();
+        trace!(
+            "resolving fn ptr call fn_ptr_ty={:?} candidates={:?}",
+            fn_ptr_ty,
+            candidates
+        );
+
+        if candidates.is_empty() {
+            warn!("unresolved fn ptr call; fn_ptr_ty={:?}", fn_ptr_ty);
+            state.add_unhandled(fn_ptr_ty);
+            return;
+        }
+
+        for candidate in candidates.into_iter() {
+            if let ty::FnDef(..) = candidate.kind() {
+                self.process_call(candidate, args, arg_tys.clone(), state, destination);
+            } else {
+                warn!("unhandled fn ptr candidate; candidate={:?}", candidate);
+                state.add_unhandled(candidate);
+            }
+        }
+    }
+
     fn process_call(
         &self,
         function_ty: Ty<'tcx>,
@@ -161,12 +230,28 @@ impl<'tcx> Collector<'tcx> {
         // Apply substitutions to the type in case it contains generics.
         let function_ty = self.current_function.substitute(function_ty, self.tcx);
 
-        // TODO: handle different call types (e.g. FnPtr).
-        if let ty::FnDef(def_id, substs) = function_ty.kind() {
+        if let ty::FnDef(def_id, substs) | ty::Generator(def_id, substs, _) = function_ty.kind() {
             if !def_id.is_local() && self.shallow {
                 return;
             }
 
+            // Foreign functions have no MIR to resolve, so they are recorded without a body and
+            // classified by the analyzer.
+            if self.tcx.is_foreign_item(def_id) {
+                trace!("found foreign call to {:?}", def_id);
+                self.function_storage_ref
+                    .borrow_mut()
+                    .insert(FunctionInfo::new_without_body(
+                        def_id.to_owned(),
+                        arg_tys.as_vec().to_owned(),
+                    ));
+                state.add_call(FunctionCall::new_without_body(
+                    def_id.to_owned(),
+                    args.to_owned(),
+                ));
+                return;
+            }
+
             // Calculate argument types, account for possible erasure.
             let plausible_functions = self.current_function.resolve(
                 def_id.to_owned(),
@@ -175,6 +260,43 @@ impl<'tcx> Collector<'tcx> {
                 self.closure_storage_ref.clone(),
                 self.tcx,
             );
+
+            // Fall back to all implementors of the trait if the receiver type has been erased. The
+            // calls to them are marked, so that a leaking implementor can be named.
+            let implementor_of = match &plausible_functions {
+                Ok(fns)
+                    if self.enumerate_implementors
+                        && is_erased_dyn_call(def_id.to_owned(), substs, fns, self.tcx) =>
+                {
+                    Some(def_id.to_owned())
+                }
+                _ => None,
+            };
+            let plausible_functions = match plausible_functions {
+                Ok(..) if implementor_of.is_some() => {
+                    self.current_function
+                        .resolve_implementors(
+                            def_id.to_owned(),
+                            substs,
+                            &arg_tys,
+                            self.closure_storage_ref.clone(),
+                            self.tcx,
+                        )
+                        .map(|(fns, uninstantiable)| {
+                            // The receiver could be an instance of a generic implementor, whose
+                            // method is never analyzed.
+                            if !uninstantiable.is_empty() {
+                                warn!(
+                                    "unhandled implementors; function_ty={:?}, impls={:?}",
+                                    function_ty, uninstantiable
+                                );
+                                state.add_unhandled(function_ty.to_owned());
+                            }
+                            fns
+                        })
+                }
+                other => other,
+            };
             trace!(
                 "plausible functions for {:?}: {:?}",
                 def_id,
@@ -225,6 +347,7 @@ impl<'tcx> Collector<'tcx> {
                                 self.function_storage_ref.clone(),
                                 self.closure_storage_ref.clone(),
                                 self.shallow,
+                                self.enumerate_implementors,
                                 self.tcx,
                             )
                             .run();
@@ -237,12 +360,20 @@ impl<'tcx> Collector<'tcx> {
                                     body.to_owned(),
                                     body.span,
                                     results.unhandled().to_owned(),
+                                    results.inline_asm().to_owned(),
                                 ),
                             );
-                            state.add_call(FunctionCall::new_with_body(
-                                function_data.instance().to_owned(),
-                                args.to_owned(),
-                            ));
+                            state.add_call(match implementor_of {
+                                Some(trait_method) => FunctionCall::new_implementor(
+                                    function_data.instance().to_owned(),
+                                    args.to_owned(),
+                                    trait_method,
+                                ),
+                                None => FunctionCall::new_with_body(
+                                    function_data.instance().to_owned(),
+                                    args.to_owned(),
+                                ),
+                            });
 
                             results.return_type(def_id, &body, self.tcx)
                         }
@@ -366,6 +497,22 @@ impl<'tcx> Analysis<'tcx> for Collector<'tcx> {
                 self.current_function.instance().def_id(),
                 self.tcx,
             );
+
+            // The body of a generator runs with the state captured upon its construction, so we
+            // treat the construction as a call into the body.
+            if let Rvalue::Aggregate(box AggregateKind::Generator(did, substs, movability), _) =
+                rvalue
+            {
+                let generator_ty = ty::Ty::new_generator(self.tcx, did, substs, movability);
+                let args = vec![Operand::Copy(place)];
+                let arg_tys = state.construct_args(
+                    &args,
+                    self.current_function.instance().def_id(),
+                    &self.substituted_body,
+                    self.tcx,
+                );
+                self.process_call(generator_ty, &args, arg_tys, state, None);
+            }
         }
     }
     fn apply_terminator_effect<'mir>(
@@ -388,7 +535,11 @@ impl<'tcx> Analysis<'tcx> for Collector<'tcx> {
                     &self.substituted_body,
                     self.tcx,
                 );
-                self.process_call(function_ty, args, arg_tys, state, Some(destination));
+                if function_ty.is_fn_ptr() {
+                    self.process_fn_ptr_call(func, args, arg_tys, state, Some(destination));
+                } else {
+                    self.process_call(function_ty, args, arg_tys, state, Some(destination));
+                }
             }
             TerminatorKind::Drop { place, .. } => {
                 let place_ty = place.ty(&self.substituted_body, self.tcx);
@@ -425,6 +576,13 @@ impl<'tcx> Analysis<'tcx> for Collector<'tcx> {
                     warn!("dropping a non-adt type: {}", place_ty.ty);
                 }
             }
+            TerminatorKind::InlineAsm { .. } => {
+                trace!(
+                    "found inline asm in {:?}",
+                    self.current_function.instance().def_id()
+                );
+                state.add_inline_asm(terminator.source_info.span);
+            }
             _ => {}
         };
         terminator.edges()
@@ -5,6 +5,7 @@ use rustc_middle::mir::{tcx::PlaceTy, Body, Local, Operand, Place, PlaceElem, Pr
 use rustc_middle::ty::{self, Ty, TyCtxt};
 use rustc_mir_dataflow::{fmt::DebugWithContext, JoinSemiLattice};
 use rustc_span::def_id::DefId;
+use rustc_span::Span;
 use rustc_utils::PlaceExt;
 use std::collections::{HashMap, HashSet};
 
@@ -75,6 +76,12 @@ fn index_ty_field<'tcx>(
             let fixed_ty = upvars.get(field_idx.index()).unwrap();
             ProjectionElem::Field(field_idx.to_owned(), fixed_ty.to_owned())
         }
+        ty::TyKind::Generator(_, generator_substs, _) => {
+            let generator_substs = generator_substs.as_generator();
+            let upvars = generator_substs.upvar_tys().into_iter().collect_vec();
+            let fixed_ty = upvars.get(field_idx.index()).unwrap();
+            ProjectionElem::Field(field_idx.to_owned(), fixed_ty.to_owned())
+        }
         ty::TyKind::Tuple(inner_tys) => {
             let fixed_ty = inner_tys.get(field_idx.index()).unwrap();
             ProjectionElem::Field(field_idx.to_owned(), fixed_ty.to_owned())
@@ -94,6 +101,7 @@ pub struct CollectorDomain<'tcx> {
     places: HashMap<NormalizedPlace<'tcx>, TrackedTy<'tcx>>,
     calls: HashSet<FunctionCall<'tcx>>,
     unhandled: HashSet<Ty<'tcx>>,
+    inline_asm: HashSet<Span>,
 }
 
 impl<'tcx> PartialEq for CollectorDomain<'tcx> {
@@ -110,6 +118,7 @@ impl<'tcx> CollectorDomain<'tcx> {
             places,
             calls: HashSet::new(),
             unhandled: HashSet::new(),
+            inline_asm: HashSet::new(),
         }
     }
 
@@ -118,6 +127,7 @@ impl<'tcx> CollectorDomain<'tcx> {
             places,
             calls,
             unhandled,
+            inline_asm,
             ..
         } = fn_info
         {
@@ -125,6 +135,7 @@ impl<'tcx> CollectorDomain<'tcx> {
                 places: places.clone(),
                 calls: calls.clone(),
                 unhandled: unhandled.clone(),
+                inline_asm: inline_asm.clone(),
             }
         } else {
             panic!("non-regular fn_info");
@@ -327,6 +338,10 @@ impl<'tcx> CollectorDomain<'tcx> {
         self.unhandled.insert(unhandled);
     }
 
+    pub fn add_inline_asm(&mut self, span: Span) {
+        self.inline_asm.insert(span);
+    }
+
     pub fn calls(&self) -> &HashSet<FunctionCall<'tcx>> {
         &self.calls
     }
@@ -334,6 +349,10 @@ impl<'tcx> CollectorDomain<'tcx> {
     pub fn unhandled(&self) -> &HashSet<Ty<'tcx>> {
         &self.unhandled
     }
+
+    pub fn inline_asm(&self) -> &HashSet<Span> {
+        &self.inline_asm
+    }
 }
 
 impl<'tcx> DebugWithContext<Collector<'tcx>> for CollectorDomain<'tcx> {}
@@ -356,6 +375,13 @@ impl<'tcx> JoinSemiLattice for CollectorDomain<'tcx> {
                 let inserted = self.unhandled.insert(unhandled_other.to_owned());
                 inserted || updated
             });
-        updated_places || updated_calls || updated_unhandled
+        let updated_inline_asm = other
+            .inline_asm
+            .iter()
+            .fold(false, |updated, inline_asm_other| {
+                let inserted = self.inline_asm.insert(inline_asm_other.to_owned());
+                inserted || updated
+            });
+        updated_places || updated_calls || updated_unhandled || updated_inline_asm
     }
 }
@@ -1,6 +1,6 @@
 use itertools::Itertools;
 use rustc_middle::mir::{AggregateKind, BinOp, CastKind, NullOp, Operand, Place, Rvalue, UnOp};
-use rustc_middle::ty::{self, TyCtxt};
+use rustc_middle::ty::{self, adjustment::PointerCoercion, TyCtxt};
 use std::collections::HashSet;
 
 use crate::body_cache::substituted_mir;
@@ -161,6 +161,11 @@ impl<'tcx> HasTrackedTy<'tcx> for Rvalue<'tcx> {
                             TrackedTy::from_ty(ty.to_owned())
                         }
                     }
+                    CastKind::PointerCoercion(PointerCoercion::ReifyFnPointer) => {
+                        // Remember the concrete function definitions that flow into the pointer.
+                        let fn_def_ty = operand.ty(&substituted_mir(instance, tcx), tcx);
+                        TrackedTy::Erased(HashSet::from([fn_def_ty]))
+                    }
                     _ => tracked_ty,
                 }
             }
@@ -263,8 +268,27 @@ impl<'tcx> HasTrackedTy<'tcx> for Rvalue<'tcx> {
                         .update_with(closure_ty, instance, upvar_tys, tcx);
                     TrackedTy::from_ty(closure_ty)
                 }
-                AggregateKind::Generator(..) => {
-                    panic!("generators are not supported")
+                AggregateKind::Generator(did, substs, movability) => {
+                    // Generators are tracked like closures, their upvars are the captured state.
+                    let generator_ty = ty::Ty::new_generator(tcx, did, substs, movability);
+                    let upvar_tys = ops
+                        .into_iter()
+                        .map(|operand| {
+                            operand.tracked_ty(
+                                type_tracker,
+                                closure_info_storage.clone(),
+                                instance,
+                                tcx,
+                            )
+                        })
+                        .collect_vec();
+                    closure_info_storage.borrow_mut().update_with(
+                        generator_ty,
+                        instance,
+                        upvar_tys,
+                        tcx,
+                    );
+                    TrackedTy::from_ty(generator_ty)
                 }
             },
             Rvalue::ShallowInitBox(_, ty) => TrackedTy::from_ty(ty::Ty::new_box(tcx, ty)),
@@ -41,6 +41,15 @@ fn extract_arg_tys<'tcx>(instance: ty::Instance<'tcx>, tcx: TyCtxt<'tcx>) -> Arg
             .collect();
             ArgTys::new(arg_tys)
         }
+        ty::Generator(_, substs, _) => {
+            // Generator bodies take the generator itself and the resume argument.
+            let generator_substs = substs.as_generator();
+            let arg_tys = vec![
+                TrackedTy::from_ty(ty),
+                TrackedTy::from_ty(generator_substs.resume_ty()),
+            ];
+            ArgTys::new(arg_tys)
+        }
         _ => panic!("argument extraction from {:?} is unsupported", instance),
     }
 }
@@ -112,13 +121,16 @@ impl<'tcx> PartialFunctionInfo<'tcx> {
         closure_info_storage: ClosureInfoStorageRef<'tcx>,
         tcx: TyCtxt<'tcx>,
     ) -> Result<PartialFunctionInfo<'tcx>, String> {
-        let inferred_args = if tcx.is_closure(instance.def_id()) {
-            arg_tys.as_closure()
+        let provided_args = extract_arg_tys(instance, tcx);
+        let merged_arg_tys = if tcx.generator_kind(instance.def_id()).is_some() {
+            // Arguments passed to `resume` or `poll` are wrapped differently than the ones the
+            // generator body receives, and the generator type is precise already.
+            provided_args
+        } else if tcx.is_closure(instance.def_id()) {
+            ArgTys::merge(arg_tys.as_closure(), provided_args)
         } else {
-            arg_tys.to_owned()
+            ArgTys::merge(arg_tys.to_owned(), provided_args)
         };
-        let provided_args = extract_arg_tys(instance, tcx);
-        let merged_arg_tys = ArgTys::merge(inferred_args, provided_args);
         if tcx.is_closure(instance.def_id()) {
             match closure_info_storage.borrow().get(&instance.def_id()) {
                 Some(closure_info) => Ok(PartialFunctionInfo::new_closure(
@@ -221,6 +233,92 @@ impl<'tcx> PartialFunctionInfo<'tcx> {
             assembled_callees
         };
 
+        self.complete(fns, closure_info_storage, tcx)
+    }
+
+    // Resolve a call on a trait object with an erased receiver to the methods of all implementors
+    // of the trait visible in the crate graph. Implementors that cannot be instantiated are
+    // returned separately, so that the call can be reported as unhandled.
+    pub fn resolve_implementors(
+        &self,
+        def_id: DefId,
+        substs: &'tcx ty::GenericArgs<'tcx>,
+        arg_tys: &ArgTys<'tcx>,
+        closure_info_storage: ClosureInfoStorageRef<'tcx>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<(Vec<PartialFunctionInfo<'tcx>>, Vec<DefId>), String> {
+        let trait_def_id = match tcx.trait_of_item(def_id) {
+            Some(trait_def_id) => trait_def_id,
+            None => return Err(format!("{:?} is not a trait method", def_id)),
+        };
+        let trait_args_count = tcx.generics_of(trait_def_id).count();
+
+        let mut uninstantiable = vec![];
+        let mut fns = vec![];
+        for impl_def_id in tcx.all_impls(trait_def_id) {
+            // Generic implementors cannot be instantiated without a concrete type, but the erased
+            // receiver could be any of their instances.
+            let has_generics = ty::GenericArgs::identity_for_item(tcx, impl_def_id)
+                .iter()
+                .any(|param| match param.unpack() {
+                    ty::GenericArgKind::Lifetime(..) => false,
+                    ty::GenericArgKind::Type(..) | ty::GenericArgKind::Const(..) => true,
+                });
+            if has_generics {
+                uninstantiable.push(impl_def_id);
+                continue;
+            }
+
+            let impl_trait_ref = match tcx.impl_trait_ref(impl_def_id) {
+                Some(impl_trait_ref) => tcx.erase_regions(impl_trait_ref.instantiate_identity()),
+                None => continue,
+            };
+
+            // Skip implementations of the same trait with different generic arguments.
+            let impl_trait_args = impl_trait_ref.args.iter().skip(1).collect_vec();
+            let call_trait_args = substs
+                .iter()
+                .take(trait_args_count)
+                .skip(1)
+                .map(|arg| tcx.erase_regions(arg))
+                .collect_vec();
+            if impl_trait_args != call_trait_args {
+                continue;
+            }
+
+            let impl_substs = tcx.mk_args_from_iter(
+                once(impl_trait_ref.self_ty().into()).chain(substs.iter().skip(1)),
+            );
+            match ty::Instance::resolve(tcx, ty::ParamEnv::reveal_all(), def_id, impl_substs) {
+                Ok(Some(instance)) => {
+                    trace!(
+                        "found implementor for def_id={:?}, impl={:?}, instance={:?}",
+                        def_id,
+                        impl_def_id,
+                        instance
+                    );
+                    fns.push(PartialFunctionInfo::assemble(
+                        instance,
+                        arg_tys,
+                        closure_info_storage.clone(),
+                        tcx,
+                    ));
+                }
+                _ => uninstantiable.push(impl_def_id),
+            }
+        }
+
+        self.complete(fns, closure_info_storage, tcx)
+            .map(|fns| (fns, uninstantiable))
+    }
+
+    // Substitute resolved callees into the current context.
+    fn complete(
+        &self,
+        fns: Vec<Result<PartialFunctionInfo<'tcx>, String>>,
+        closure_info_storage: ClosureInfoStorageRef<'tcx>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Result<Vec<PartialFunctionInfo<'tcx>>, String> {
         fns.into_iter()
             .map(|fn_data| {
                 let fn_data = fn_data?;
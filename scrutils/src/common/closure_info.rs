@@ -12,7 +12,7 @@ pub struct ClosureInfo<'tcx> {
 impl<'tcx> ClosureInfo<'tcx> {
     pub fn extract_instance(&self, tcx: TyCtxt<'tcx>) -> ty::Instance<'tcx> {
         match self.with_substs.kind() {
-            ty::TyKind::Closure(def_id, substs) => {
+            ty::TyKind::Closure(def_id, substs) | ty::TyKind::Generator(def_id, substs, _) => {
                 ty::Instance::resolve(tcx, ty::ParamEnv::reveal_all(), *def_id, substs)
                     .unwrap()
                     .unwrap()
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The reason why a region or a function called from it could not be verified to be pure.
+///
+/// Reasons are serialized as `{"code": "<code>"}`, with an additional `detail` field for
+/// foreign calls, leaking implementors and internal errors. The codes are stable and meant to be
+/// consumed by other tools.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "code", content = "detail", rename_all = "snake_case")]
+pub enum FailureReason {
+    ErasedArgs,
+    MutableArgs,
+    UnhandledCallType,
+    RawPointerWrite,
+    MutableTransmute,
+    InteriorMutation,
+    GlobalStateWrite,
+    InlineAsm,
+    ForeignCall(ForeignCallKind),
+    NoMirAvailable,
+    IntrinsicNotAllowlisted,
+    RecursionLimit,
+    LeakingCall,
+    LeakingImplementor(String),
+    InternalError(String),
+}
+
+impl FailureReason {
+    pub fn code(&self) -> &'static str {
+        match self {
+            FailureReason::ErasedArgs => "erased_args",
+            FailureReason::MutableArgs => "mutable_args",
+            FailureReason::UnhandledCallType => "unhandled_call_type",
+            FailureReason::RawPointerWrite => "raw_pointer_write",
+            FailureReason::MutableTransmute => "mutable_transmute",
+            FailureReason::InteriorMutation => "interior_mutation",
+            FailureReason::GlobalStateWrite => "global_state_write",
+            FailureReason::InlineAsm => "inline_asm",
+            FailureReason::ForeignCall(..) => "foreign_call",
+            FailureReason::NoMirAvailable => "no_mir_available",
+            FailureReason::IntrinsicNotAllowlisted => "intrinsic_not_allowlisted",
+            FailureReason::RecursionLimit => "recursion_limit",
+            FailureReason::LeakingCall => "leaking_call",
+            FailureReason::LeakingImplementor(..) => "leaking_implementor",
+            FailureReason::InternalError(..) => "internal_error",
+        }
+    }
+}
+
+impl fmt::Display for FailureReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FailureReason::ErasedArgs => write!(f, "erased args detected"),
+            FailureReason::MutableArgs => write!(f, "mutable arguments detected"),
+            FailureReason::UnhandledCallType => write!(f, "call of an unhandled type"),
+            FailureReason::RawPointerWrite => write!(f, "write through a raw pointer"),
+            FailureReason::MutableTransmute => {
+                write!(f, "mutable access obtained from immutable data")
+            }
+            FailureReason::InteriorMutation => {
+                write!(f, "write to interior mutable state visible outside")
+            }
+            FailureReason::GlobalStateWrite => write!(f, "important data written to global state"),
+            FailureReason::InlineAsm => write!(f, "inline assembly"),
+            FailureReason::ForeignCall(kind) => write!(f, "call to a foreign function: {}", kind),
+            FailureReason::NoMirAvailable => write!(f, "no MIR available"),
+            FailureReason::IntrinsicNotAllowlisted => {
+                write!(f, "call to an intrinsic that is not allowlisted")
+            }
+            FailureReason::RecursionLimit => write!(f, "call depth exceeds the recursion limit"),
+            FailureReason::LeakingCall => {
+                write!(f, "unable to ascertain purity of inner function call")
+            }
+            FailureReason::LeakingImplementor(implementor) => write!(
+                f,
+                "unable to ascertain purity of `{}`, an implementor of an erased `dyn` call",
+                implementor
+            ),
+            FailureReason::InternalError(message) => write!(f, "internal error: {}", message),
+        }
+    }
+}
+
+/// Why a call to a foreign function could not be verified to be pure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForeignCallKind {
+    SyscallWrapper,
+    DeclaredImpure,
+    Unknown,
+}
+
+impl fmt::Display for ForeignCallKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ForeignCallKind::SyscallWrapper => write!(f, "libc system call wrapper"),
+            ForeignCallKind::DeclaredImpure => write!(f, "declared impure in the config"),
+            ForeignCallKind::Unknown => write!(f, "no summary available"),
+        }
+    }
+}
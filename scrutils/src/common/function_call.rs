@@ -5,11 +5,15 @@ use rustc_span::def_id::DefId;
 use serde::ser::SerializeStruct;
 use serde::Serialize;
 
+use crate::common::identifier::FunctionIdentifier;
+
 #[derive(Clone, Debug, Hash, PartialEq)]
 pub enum FunctionCall<'tcx> {
     WithBody {
         instance: ty::Instance<'tcx>,
         args: Vec<Operand<'tcx>>,
+        // The trait method of the erased `dyn` call this implementor was enumerated for.
+        implementor_of: Option<DefId>,
     },
     WithoutBody {
         def_id: DefId,
@@ -21,7 +25,22 @@ impl<'tcx> Eq for FunctionCall<'tcx> {}
 
 impl<'tcx> FunctionCall<'tcx> {
     pub fn new_with_body(instance: ty::Instance<'tcx>, args: Vec<Operand<'tcx>>) -> Self {
-        Self::WithBody { instance, args }
+        Self::WithBody {
+            instance,
+            args,
+            implementor_of: None,
+        }
+    }
+    pub fn new_implementor(
+        instance: ty::Instance<'tcx>,
+        args: Vec<Operand<'tcx>>,
+        trait_method: DefId,
+    ) -> Self {
+        Self::WithBody {
+            instance,
+            args,
+            implementor_of: Some(trait_method),
+        }
     }
     pub fn new_without_body(def_id: DefId, args: Vec<Operand<'tcx>>) -> Self {
         Self::WithoutBody { def_id, args }
@@ -37,6 +56,18 @@ impl<'tcx> FunctionCall<'tcx> {
             Self::WithoutBody { def_id, .. } => def_id.to_owned(),
         }
     }
+    pub fn implementor_of(&self) -> Option<DefId> {
+        match self {
+            Self::WithBody { implementor_of, .. } => implementor_of.to_owned(),
+            Self::WithoutBody { .. } => None,
+        }
+    }
+    pub fn instance(&self) -> Option<ty::Instance<'tcx>> {
+        match self {
+            Self::WithBody { instance, .. } => Some(instance.to_owned()),
+            Self::WithoutBody { .. } => None,
+        }
+    }
 }
 
 impl<'tcx> Serialize for FunctionCall<'tcx> {
@@ -44,8 +75,9 @@ impl<'tcx> Serialize for FunctionCall<'tcx> {
     where
         S: serde::Serializer,
     {
-        let mut state = serializer.serialize_struct("FunctionCall", 2)?;
+        let mut state = serializer.serialize_struct("FunctionCall", 4)?;
         state.serialize_field("def_id", format!("{:?}", self.def_id()).as_str())?;
+        state.serialize_field("name", &FunctionIdentifier::current(self.def_id()))?;
         state.serialize_field(
             "args",
             &self
@@ -54,6 +86,12 @@ impl<'tcx> Serialize for FunctionCall<'tcx> {
                 .map(|arg| format!("{:?}", arg))
                 .collect_vec(),
         )?;
+        state.serialize_field(
+            "implementor_of",
+            &self
+                .implementor_of()
+                .map(|trait_method| format!("{:?}", trait_method)),
+        )?;
         state.end()
     }
 }
@@ -7,6 +7,7 @@ use serde::ser::{Serialize, SerializeStruct};
 use std::collections::{HashMap, HashSet};
 
 use crate::common::function_call::FunctionCall;
+use crate::common::identifier::FunctionIdentifier;
 use crate::common::normalized_place::NormalizedPlace;
 use crate::common::tracked_ty::TrackedTy;
 
@@ -19,6 +20,7 @@ pub enum FunctionInfo<'tcx> {
         body: Body<'tcx>,
         span: Span,
         unhandled: HashSet<Ty<'tcx>>,
+        inline_asm: HashSet<Span>,
     },
     WithoutBody {
         def_id: DefId,
@@ -34,6 +36,7 @@ impl<'tcx> FunctionInfo<'tcx> {
         body: Body<'tcx>,
         span: Span,
         unhandled: HashSet<Ty<'tcx>>,
+        inline_asm: HashSet<Span>,
     ) -> Self {
         FunctionInfo::WithBody {
             instance,
@@ -42,6 +45,7 @@ impl<'tcx> FunctionInfo<'tcx> {
             body,
             span,
             unhandled,
+            inline_asm,
         }
     }
 
@@ -66,6 +70,20 @@ impl<'tcx> FunctionInfo<'tcx> {
         }
     }
 
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            FunctionInfo::WithBody { span, .. } => Some(span.to_owned()),
+            _ => None,
+        }
+    }
+
+    pub fn inline_asm(&self) -> Option<&HashSet<Span>> {
+        match self {
+            FunctionInfo::WithBody { inline_asm, .. } => Some(inline_asm),
+            _ => None,
+        }
+    }
+
     pub fn calls(&self) -> Option<&HashSet<FunctionCall<'tcx>>> {
         match self {
             FunctionInfo::WithBody { calls, .. } => Some(calls),
@@ -86,6 +104,7 @@ impl<'tcx> PartialEq for FunctionInfo<'tcx> {
                     calls: l_calls,
                     span: l_span,
                     unhandled: l_unhandled,
+                    inline_asm: l_inline_asm,
                     ..
                 },
                 Self::WithBody {
@@ -94,6 +113,7 @@ impl<'tcx> PartialEq for FunctionInfo<'tcx> {
                     calls: r_calls,
                     span: r_span,
                     unhandled: r_unhandled,
+                    inline_asm: r_inline_asm,
                     ..
                 },
             ) => {
@@ -102,6 +122,7 @@ impl<'tcx> PartialEq for FunctionInfo<'tcx> {
                     && l_calls == r_calls
                     && l_span == r_span
                     && l_unhandled == r_unhandled
+                    && l_inline_asm == r_inline_asm
             }
             (
                 Self::WithoutBody {
@@ -129,16 +150,25 @@ impl<'tcx> Serialize for FunctionInfo<'tcx> {
                 ref calls,
                 ref span,
                 ref unhandled,
+                ref inline_asm,
                 ..
             } => {
-                let mut tv = serializer.serialize_struct("FunctionInfo", 5)?;
+                let mut tv = serializer.serialize_struct("FunctionInfo", 7)?;
                 tv.serialize_field("def_id", format!("{:?}", instance.def_id()).as_str())?;
+                tv.serialize_field("name", &FunctionIdentifier::current(instance.def_id()))?;
                 tv.serialize_field("calls", calls)?;
                 tv.serialize_field("span", format!("{:?}", span).as_str())?;
                 tv.serialize_field(
                     "unhandled",
                     &unhandled.iter().map(|ty| format!("{:?}", ty)).collect_vec(),
                 )?;
+                tv.serialize_field(
+                    "inline_asm",
+                    &inline_asm
+                        .iter()
+                        .map(|span| format!("{:?}", span))
+                        .collect_vec(),
+                )?;
                 tv.serialize_field("has_body", &true)?;
                 tv.end()
             }
@@ -146,9 +176,14 @@ impl<'tcx> Serialize for FunctionInfo<'tcx> {
                 ref def_id,
                 ref tracked_args,
             } => {
-                let mut tv = serializer.serialize_struct("FunctionInfo", 3)?;
+                let mut tv = serializer.serialize_struct("FunctionInfo", 5)?;
                 tv.serialize_field("def_id", format!("{:?}", def_id).as_str())?;
+                tv.serialize_field("name", &FunctionIdentifier::current(*def_id))?;
                 tv.serialize_field("tracked_args", &tracked_args)?;
+                tv.serialize_field(
+                    "foreign",
+                    &ty::tls::with(|tcx| tcx.is_foreign_item(*def_id)),
+                )?;
                 tv.serialize_field("has_body", &false)?;
                 tv.end()
             }
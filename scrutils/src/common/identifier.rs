@@ -0,0 +1,77 @@
+use rustc_hir::def_id::{CrateNum, DefId, LOCAL_CRATE};
+use rustc_middle::ty::print::{with_no_trimmed_paths, with_no_visible_paths};
+use rustc_middle::ty::{self, TyCtxt};
+use serde::Serialize;
+use std::env;
+
+/// A human-readable identifier of a function that, unlike `DefId`s, survives recompilation.
+#[derive(Clone, Debug, Serialize)]
+pub struct FunctionIdentifier {
+    path: String,
+    crate_name: String,
+    crate_version: Option<String>,
+    stable_hash: String,
+}
+
+impl FunctionIdentifier {
+    pub fn new(def_id: DefId, tcx: TyCtxt) -> Self {
+        let path = readable_path(def_id, tcx);
+        let crate_name = tcx.crate_name(def_id.krate).to_string();
+        let crate_version = crate_version(def_id.krate, tcx);
+        let stable_hash = fnv1a(
+            format!(
+                "{}@{}::{}",
+                crate_name,
+                crate_version.as_deref().unwrap_or_default(),
+                path
+            )
+            .as_bytes(),
+        );
+        FunctionIdentifier {
+            path,
+            crate_name,
+            crate_version,
+            stable_hash: format!("{:016x}", stable_hash),
+        }
+    }
+
+    /// Retrieve the identifier from the type context of the current thread, for use in
+    /// serialization.
+    pub fn current(def_id: DefId) -> Self {
+        ty::tls::with(|tcx| FunctionIdentifier::new(def_id, tcx))
+    }
+}
+
+/// Full path of a definition, e.g. `core::intrinsics::transmute`, which does not depend on the
+/// crate being analyzed or on the compiler session.
+pub fn readable_path(def_id: DefId, tcx: TyCtxt) -> String {
+    with_no_visible_paths!(with_no_trimmed_paths!(tcx.def_path_str(def_id)))
+}
+
+// Cargo only tells the version of the crate being compiled, so the versions of dependencies are
+// recovered from their source directories, e.g. `.../registry/src/.../serde-1.0.188/src/lib.rs`.
+fn crate_version(krate: CrateNum, tcx: TyCtxt) -> Option<String> {
+    if krate == LOCAL_CRATE {
+        return env::var("CARGO_PKG_VERSION").ok();
+    }
+    let package_prefix = format!("{}-", tcx.crate_name(krate).as_str().replace('_', "-"));
+    let crate_root = tcx
+        .sess
+        .source_map()
+        .lookup_source_file(tcx.def_span(krate.as_def_id()).lo());
+    let crate_root_path = crate_root.name.prefer_local().to_string();
+    crate_root_path.split(['/', '\\']).find_map(|component| {
+        component
+            .replace('_', "-")
+            .strip_prefix(&package_prefix)
+            .filter(|version| version.starts_with(|c: char| c.is_ascii_digit()))
+            .map(|_| component[package_prefix.len()..].to_owned())
+    })
+}
+
+// 64-bit FNV-1a, which, unlike the hashers of the standard library, is fixed across releases.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
@@ -1,7 +1,9 @@
 mod arg_tys;
 mod closure_info;
+mod failure_reason;
 mod function_call;
 mod function_info;
+mod identifier;
 mod normalized_place;
 mod tracked_ty;
 
@@ -9,7 +11,9 @@ pub mod storage;
 
 pub use arg_tys::ArgTys;
 pub use closure_info::ClosureInfo;
+pub use failure_reason::{FailureReason, ForeignCallKind};
 pub use function_call::FunctionCall;
 pub use function_info::FunctionInfo;
+pub use identifier::{fnv1a, readable_path, FunctionIdentifier};
 pub use normalized_place::NormalizedPlace;
 pub use tracked_ty::TrackedTy;
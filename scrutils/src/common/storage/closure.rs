@@ -23,7 +23,7 @@ impl<'tcx> ClosureInfoStorage<'tcx> {
         self.closures.get(&def_id)
     }
 
-    // TODO: enforce that only closures are passed to this function.
+    // TODO: enforce that only closures and generators are passed to this function.
     pub fn update_with(
         &mut self,
         closure_ty: Ty<'tcx>,
@@ -31,7 +31,9 @@ impl<'tcx> ClosureInfoStorage<'tcx> {
         upvars: Vec<TrackedTy<'tcx>>,
         tcx: TyCtxt<'tcx>,
     ) {
-        if let ty::TyKind::Closure(closure_def_id, ..) = closure_ty.kind() {
+        if let ty::TyKind::Closure(closure_def_id, ..) | ty::TyKind::Generator(closure_def_id, ..) =
+            closure_ty.kind()
+        {
             let resolved_closure_ty =
                 outer_instance.subst_mir(tcx, ty::EarlyBinder::bind(&closure_ty));
             self.closures
@@ -14,7 +14,11 @@ fn contains_erased<'tcx>(ty: Ty<'tcx>) -> bool {
         },
         _ => false,
     });
-    !ty.contains_closure() && contains_erased_type
+    let contains_generator = ty.walk().any(|ty| match ty.unpack() {
+        ty::GenericArgKind::Type(ty) => ty.is_generator(),
+        _ => false,
+    });
+    !ty.contains_closure() && !contains_generator && contains_erased_type
 }
 
 fn transpose<T>(v: Vec<Vec<T>>) -> Vec<Vec<T>> {
@@ -0,0 +1,53 @@
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::TyCtxt;
+use serde::{Deserialize, Serialize};
+
+// An important argument of a particular function, either by its position (starting from 0) or by
+// its name.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ImportantArg {
+    Position(usize),
+    Name(String),
+}
+
+impl ImportantArg {
+    // Resolve to the argument local (starting from 1).
+    pub fn to_local(&self, def_id: DefId, tcx: TyCtxt) -> Result<usize, String> {
+        let arg_names = tcx.fn_arg_names(def_id);
+        match self {
+            ImportantArg::Position(position) => {
+                if *position < arg_names.len() {
+                    Ok(position + 1)
+                } else {
+                    Err(format!(
+                        "important argument position {} is out of bounds for {:?}",
+                        position, def_id
+                    ))
+                }
+            }
+            ImportantArg::Name(name) => arg_names
+                .iter()
+                .position(|ident| ident.name.as_str() == name)
+                .map(|position| position + 1)
+                .ok_or(format!(
+                    "important argument {} is not a parameter of {:?}",
+                    name, def_id
+                )),
+        }
+    }
+}
+
+pub fn resolve_important_args(
+    important_args: &Vec<ImportantArg>,
+    def_id: DefId,
+    tcx: TyCtxt,
+) -> Result<Vec<usize>, String> {
+    let mut locals = important_args
+        .iter()
+        .map(|arg| arg.to_local(def_id, tcx))
+        .collect::<Result<Vec<_>, _>>()?;
+    locals.sort();
+    locals.dedup();
+    Ok(locals)
+}
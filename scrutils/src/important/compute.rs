@@ -21,6 +21,21 @@ pub fn compute_dependent_locals<'tcx>(
     targets: Vec<Vec<(Place<'tcx>, LocationOrArg)>>,
     direction: Direction,
 ) -> Vec<Local> {
+    // Merge location dependencies of all targets.
+    compute_dependent_locals_per_target(tcx, def_id, targets, direction)
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+// Same as above, but keeps the locals of each target separate, so that multiple targets can be
+// queried while running the analysis only once.
+pub fn compute_dependent_locals_per_target<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    targets: Vec<Vec<(Place<'tcx>, LocationOrArg)>>,
+    direction: Direction,
+) -> Vec<Vec<Local>> {
     let cache = BodyCache::new(tcx);
     let body_with_facts = cache
         .get(def_id)
@@ -37,46 +52,46 @@ pub fn compute_dependent_locals<'tcx>(
     };
 
     trace!("computing location dependencies for {:?}, {:?}", def_id, targets);
-    // Use Flowistry to compute the locations and places influenced by the target.
-    let location_deps =
-        flowistry::infoflow::compute_dependencies(&results, targets.clone(), direction)
-            .into_iter()
-            .reduce(|acc, e| {
-                let mut new_acc = acc.clone();
-                new_acc.union(&e);
-                new_acc
-            })
-            .unwrap();
+    // Use Flowistry to compute the locations and places influenced by each target.
+    let location_deps = flowistry::infoflow::compute_dependencies(&results, targets, direction);
 
-    // Merge location dependencies and extract locals from them.
+    // Extract locals from location dependencies.
     location_deps
         .iter()
-        .map(|dep| match dep {
-            LocationOrArg::Location(location) => {
-                let stmt_or_terminator = body_with_facts.body().stmt_at(*location);
-                match stmt_or_terminator {
-                    Either::Left(stmt) => match &stmt.kind {
-                        StatementKind::Assign(assign) => {
-                            let (place, _) = **assign;
-                            vec![place.local]
-                        }
-                        _ => {
-                            unimplemented!()
+        .map(|target_deps| {
+            target_deps
+                .iter()
+                .map(|dep| match dep {
+                    LocationOrArg::Location(location) => {
+                        let stmt_or_terminator = body_with_facts.body().stmt_at(*location);
+                        match stmt_or_terminator {
+                            Either::Left(stmt) => match &stmt.kind {
+                                StatementKind::Assign(assign) => {
+                                    let (place, _) = **assign;
+                                    vec![place.local]
+                                }
+                                _ => {
+                                    unimplemented!()
+                                }
+                            },
+                            Either::Right(terminator) => match &terminator.kind {
+                                TerminatorKind::Call { destination, .. } => {
+                                    vec![destination.local]
+                                }
+                                TerminatorKind::Yield { resume_arg, .. } => {
+                                    vec![resume_arg.local]
+                                }
+                                TerminatorKind::SwitchInt { .. } => vec![],
+                                _ => {
+                                    unimplemented!()
+                                }
+                            },
                         }
-                    },
-                    Either::Right(terminator) => match &terminator.kind {
-                        TerminatorKind::Call { destination, .. } => {
-                            vec![destination.local]
-                        }
-                        TerminatorKind::SwitchInt { .. } => vec![],
-                        _ => {
-                            unimplemented!()
-                        }
-                    },
-                }
-            }
-            LocationOrArg::Arg(local) => vec![*local],
+                    }
+                    LocationOrArg::Arg(local) => vec![*local],
+                })
+                .flatten()
+                .collect()
         })
-        .flatten()
         .collect()
 }
@@ -34,6 +34,11 @@ impl Serialize for ImportantLocals {
 
 impl ImportantLocals {
     pub fn from_important_args(important_args: Vec<usize>, def_id: DefId, tcx: TyCtxt) -> Self {
+        if !is_mir_available(def_id, tcx) {
+            return ImportantLocals::from_locals(HashSet::from_iter(
+                important_args.into_iter().map(|arg| Local::from_usize(arg)),
+            ));
+        }
         let targets = vec![important_args
             .iter()
             .map(|arg| {
@@ -47,35 +52,38 @@ impl ImportantLocals {
         ))
     }
 
-    fn from_locals(locals: HashSet<Local>) -> Self {
+    pub fn from_locals(locals: HashSet<Local>) -> Self {
         Self { locals }
     }
 
+    pub fn locals(&self) -> &HashSet<Local> {
+        &self.locals
+    }
+
     pub fn is_empty(&self) -> bool {
         self.locals.is_empty()
     }
 
-    // Construct new important locals which influence args.
-    pub fn transition(
+    // Compute which arguments of the callee are influenced by important locals.
+    pub fn important_args_to_callee(
         &self,
         args_from_caller: &Vec<Operand>,
         callee_def_id: DefId,
         tcx: TyCtxt,
-    ) -> Self {
+    ) -> Vec<usize> {
         // Constructors are final and have no important locals.
         if tcx.is_constructor(callee_def_id) {
-            return ImportantLocals::from_locals(HashSet::new());
+            return vec![];
         }
-        // Construct targets of the arguments.
-        let important_args_to_callee = if tcx.is_closure(callee_def_id) {
+        if tcx.is_closure(callee_def_id) {
             // We need to propagate label to the closure arguments correctly, as they use a
             // different calling convention.
             if args_from_caller.len() == 2 {
                 (0..num_args_in_body(callee_def_id, tcx))
-                    .map(|i| Local::from_usize(i + 1))
+                    .map(|i| i + 1)
                     .collect_vec()
             } else if args_from_caller.len() == 1 {
-                vec![Local::from_usize(1)]
+                vec![1]
             } else {
                 panic!("Closure #args invariant violated.");
             }
@@ -89,34 +97,13 @@ impl ImportantLocals {
                         .and_then(|local| {
                             if self.locals.contains(&local) {
                                 // Need to add 1 because arguments' locals start with 1.
-                                Some(Local::from_usize(i + 1))
+                                Some(i + 1)
                             } else {
                                 None
                             }
                         })
                 })
                 .collect_vec()
-        };
-        if is_mir_available(callee_def_id, tcx) {
-            let new_important_arg_targets = vec![important_args_to_callee
-                .into_iter()
-                .map(|arg_local| {
-                    let arg_place = Place::make(arg_local, &[], tcx);
-                    (arg_place, LocationOrArg::Arg(arg_local))
-                })
-                .collect()];
-            // Compute new dependencies for all important args.
-            ImportantLocals::from_locals(HashSet::from_iter(
-                compute_dependent_locals(
-                    tcx,
-                    callee_def_id,
-                    new_important_arg_targets,
-                    Direction::Forward,
-                )
-                .into_iter(),
-            ))
-        } else {
-            ImportantLocals::from_locals(HashSet::from_iter(important_args_to_callee.into_iter()))
         }
     }
 }
@@ -1,4 +1,7 @@
+mod arg;
 mod compute;
 mod important;
 
+pub use arg::{resolve_important_args, ImportantArg};
+pub use compute::compute_dependent_locals_per_target;
 pub use important::ImportantLocals;
@@ -3,6 +3,7 @@
 extern crate either;
 extern crate polonius_engine;
 extern crate rustc_abi;
+extern crate rustc_ast;
 extern crate rustc_borrowck;
 extern crate rustc_const_eval;
 extern crate rustc_data_structures;
@@ -28,9 +29,13 @@ mod important;
 mod precheck;
 mod selector;
 
-pub use analyzer::{run as run_analysis, PurityAnalysisResult};
+pub use analyzer::{
+    run as run_analysis, FfiSummary, FunctionWithMetadata, PurityAnalysisResult, RawPtrHeuristic,
+    VerdictCache,
+};
 pub use body_cache::{dump_mir_and_borrowck_facts, substituted_mir};
 pub use collector::Collector;
-pub use important::ImportantLocals;
-pub use precheck::precheck;
-pub use selector::{select_functions, select_pprs};
+pub use common::{fnv1a, readable_path, FailureReason, ForeignCallKind};
+pub use important::{resolve_important_args, ImportantArg, ImportantLocals};
+pub use precheck::{mut_ref_args, precheck};
+pub use selector::{select_functions, select_pprs, Annotation};
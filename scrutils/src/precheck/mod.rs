@@ -3,9 +3,28 @@ use rustc_middle::mir::Mutability;
 use rustc_middle::ty::{self, TyCtxt};
 
 use crate::body_cache::substituted_mir;
-use crate::common::TrackedTy;
+use crate::common::{FailureReason, TrackedTy};
 
-pub fn precheck<'tcx>(instance: ty::Instance<'tcx>, tcx: TyCtxt<'tcx>) -> Result<(), String> {
+// Retrieve the positions of all arguments passed by mutable reference.
+pub fn mut_ref_args<'tcx>(instance: ty::Instance<'tcx>, tcx: TyCtxt<'tcx>) -> Vec<usize> {
+    let body = substituted_mir(&instance, tcx);
+    (1..=body.arg_count)
+        .filter(|local| {
+            let arg_ty = body.local_decls[(*local).into()].ty;
+            if let ty::TyKind::Ref(.., mutbl) = arg_ty.kind() {
+                mutbl.to_owned() == Mutability::Mut
+            } else {
+                false
+            }
+        })
+        .collect()
+}
+
+pub fn precheck<'tcx>(
+    instance: ty::Instance<'tcx>,
+    allow_mut_args: bool,
+    tcx: TyCtxt<'tcx>,
+) -> Result<(), FailureReason> {
     let body = substituted_mir(&instance, tcx);
     // Create initial argument types.
     let arg_tys = (1..=body.arg_count)
@@ -22,24 +41,12 @@ pub fn precheck<'tcx>(instance: ty::Instance<'tcx>, tcx: TyCtxt<'tcx>) -> Result
     });
 
     if contains_unresolved_generics {
-        return Err(String::from("erased args detected"));
+        return Err(FailureReason::ErasedArgs);
     }
 
-    // Check for mutable arguments.
-    let contains_mutable_args = arg_tys.iter().any(|arg| {
-        let main_ty = match arg {
-            TrackedTy::Present(ty) => ty,
-            TrackedTy::Erased(..) => unreachable!(),
-        };
-        if let ty::TyKind::Ref(.., mutbl) = main_ty.kind() {
-            return mutbl.to_owned() == Mutability::Mut;
-        } else {
-            return false;
-        }
-    });
-
-    if contains_mutable_args {
-        return Err(String::from("mutable arguments detected"));
+    // Check for mutable arguments, unless they are allowed as output sinks.
+    if !allow_mut_args && !mut_ref_args(instance, tcx).is_empty() {
+        return Err(FailureReason::MutableArgs);
     }
 
     Ok(())
@@ -0,0 +1,153 @@
+use itertools::Itertools;
+use rustc_ast::{AttrKind, Attribute, LitKind};
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::symbol::Symbol;
+
+use crate::important::ImportantArg;
+
+const TOOL_NAME: &str = "scrutinizer";
+const TOOL_ATTRS: [&str; 3] = ["pure", "impure", "important"];
+
+// Purity annotation and analysis options attached to a region.
+#[derive(Clone, Debug)]
+pub struct Annotation {
+    pure: bool,
+    important_args: Option<Vec<ImportantArg>>,
+}
+
+impl Annotation {
+    pub fn pure() -> Self {
+        Annotation {
+            pure: true,
+            important_args: None,
+        }
+    }
+
+    pub fn is_pure(&self) -> bool {
+        self.pure
+    }
+
+    pub fn important_args(&self) -> Option<&Vec<ImportantArg>> {
+        self.important_args.as_ref()
+    }
+
+    // Read the annotation of a function from either the tool attributes, e.g.
+    //
+    //     #[scrutinizer::pure]
+    //     #[scrutinizer::important(0, secret)]
+    //
+    // or, if none are present, from the legacy `#[doc = "pure"]` form.
+    pub fn from_attrs(def_id: DefId, tcx: TyCtxt) -> Self {
+        let tool_attrs = collect_tool_attrs(tcx.get_attrs_unchecked(def_id), tcx);
+        let pure = purity_from_tool_attrs(&tool_attrs).unwrap_or_else(|| {
+            tcx.get_attrs(def_id, Symbol::intern("doc"))
+                .filter_map(|attr| attr.doc_str())
+                .any(|symbol| symbol == Symbol::intern("pure"))
+        });
+        Annotation {
+            pure,
+            important_args: important_args_from_tool_attrs(&tool_attrs, tcx),
+        }
+    }
+
+    // Read the annotation of the instantiations of generic functions in a statement, e.g.
+    //
+    //     #[scrutinizer::impure]
+    //     let _ = execute(a, leaky_closure);
+    //
+    // which overrides the annotation of the generic functions themselves.
+    pub fn from_call_site_attrs(attrs: &[Attribute], tcx: TyCtxt) -> Option<Self> {
+        let tool_attrs = collect_tool_attrs(attrs, tcx);
+        purity_from_tool_attrs(&tool_attrs).map(|pure| Annotation {
+            pure,
+            important_args: important_args_from_tool_attrs(&tool_attrs, tcx),
+        })
+    }
+}
+
+// Collect the tool attributes, reporting misspelled ones instead of silently ignoring them.
+fn collect_tool_attrs<'a>(attrs: &'a [Attribute], tcx: TyCtxt) -> Vec<(Symbol, &'a Attribute)> {
+    let tool_attrs = attrs
+        .iter()
+        .filter_map(|attr| tool_attr_name(attr).map(|name| (name, attr)))
+        .collect_vec();
+    for (name, attr) in tool_attrs.iter() {
+        if !TOOL_ATTRS.contains(&name.as_str()) {
+            tcx.sess.span_err(
+                attr.span,
+                format!("unknown attribute `{}::{}`", TOOL_NAME, name),
+            );
+        }
+    }
+    tool_attrs
+}
+
+fn has_tool_attr(tool_attrs: &[(Symbol, &Attribute)], name: &str) -> bool {
+    tool_attrs
+        .iter()
+        .any(|(attr_name, ..)| attr_name.as_str() == name)
+}
+
+// Purity given by the tool attributes, if any of them specifies it.
+fn purity_from_tool_attrs(tool_attrs: &[(Symbol, &Attribute)]) -> Option<bool> {
+    let pure = has_tool_attr(tool_attrs, "pure");
+    let impure = has_tool_attr(tool_attrs, "impure");
+    (pure || impure).then_some(pure && !impure)
+}
+
+fn important_args_from_tool_attrs(
+    tool_attrs: &[(Symbol, &Attribute)],
+    tcx: TyCtxt,
+) -> Option<Vec<ImportantArg>> {
+    tool_attrs
+        .iter()
+        .filter(|(name, ..)| name.as_str() == "important")
+        .map(|(.., attr)| parse_important_args(attr, tcx))
+        .reduce(|mut acc, args| {
+            acc.extend(args);
+            acc
+        })
+}
+
+// Retrieve the name of a `#[scrutinizer::<name>]` attribute.
+fn tool_attr_name(attr: &Attribute) -> Option<Symbol> {
+    match &attr.kind {
+        AttrKind::Normal(normal) => match normal.item.path.segments.as_slice() {
+            [tool, name] if tool.ident.name.as_str() == TOOL_NAME => Some(name.ident.name),
+            _ => None,
+        },
+        AttrKind::DocComment(..) => None,
+    }
+}
+
+// Parse argument positions and names from `#[scrutinizer::important(0, secret)]`, reporting
+// malformed arguments on their span.
+fn parse_important_args(attr: &Attribute, tcx: TyCtxt) -> Vec<ImportantArg> {
+    let items = attr.meta_item_list().unwrap_or_default();
+    if items.is_empty() {
+        tcx.sess.span_err(
+            attr.span,
+            format!(
+                "`{}::important` expects at least one argument position or name",
+                TOOL_NAME
+            ),
+        );
+    }
+    items
+        .iter()
+        .filter_map(|item| {
+            if let Some(LitKind::Int(position, ..)) = item.lit().map(|lit| &lit.kind) {
+                Some(ImportantArg::Position(*position as usize))
+            } else if let Some(ident) = item.ident().filter(|_| item.is_word()) {
+                Some(ImportantArg::Name(ident.name.to_string()))
+            } else {
+                tcx.sess.span_err(
+                    item.span(),
+                    "malformed important argument, expected a position or a name",
+                );
+                None
+            }
+        })
+        .collect()
+}
@@ -1,38 +1,192 @@
-use rustc_hir::ItemKind;
-use rustc_middle::ty::{self, TyCtxt};
+use rustc_hir::def_id::DefId;
+use rustc_hir::intravisit;
+use rustc_hir::{ConstContext, ImplItemKind, ItemKind, Stmt, StmtKind, TraitFn, TraitItemKind};
+use rustc_middle::hir::nested_filter::OnlyBodies;
+use rustc_middle::lint::in_external_macro;
+use rustc_middle::mir::visit::Visitor;
+use rustc_middle::mir::{Body, Location, Terminator, TerminatorKind};
+use rustc_middle::ty::{self, TyCtxt, TypeVisitableExt};
+use rustc_span::Span;
+use std::collections::{HashMap, HashSet};
 
-pub fn select_functions<'tcx>(tcx: TyCtxt<'tcx>) -> Vec<(ty::Instance<'tcx>, bool)> {
-    tcx.hir()
-        .items()
-        .filter_map(|item_id| {
-            let hir = tcx.hir();
-            let item = hir.item(item_id);
-            let def_id = item.owner_id.to_def_id();
-            let annotated_pure = tcx
-                .get_attr(def_id, rustc_span::symbol::Symbol::intern("doc"))
-                .and_then(|attr| attr.doc_str())
-                .and_then(|symbol| Some(symbol == rustc_span::symbol::Symbol::intern("pure")))
-                .unwrap_or(false);
-
-            if let ItemKind::Fn(..) = &item.kind {
-                // Sanity check for generics.
-                let has_generics =
-                    ty::GenericArgs::identity_for_item(tcx, def_id)
-                        .iter()
-                        .any(|param| match param.unpack() {
-                            ty::GenericArgKind::Lifetime(..) => false,
-                            ty::GenericArgKind::Type(..) | ty::GenericArgKind::Const(..) => true,
-                        });
-
-                if has_generics {
-                    return None;
-                }
+use crate::selector::annotation::Annotation;
+
+// Collects the statements annotated with the purity of the instantiations in them.
+struct CallSiteAnnotationCollector<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    annotations: Vec<(Span, Annotation)>,
+}
+
+impl<'tcx> intravisit::Visitor<'tcx> for CallSiteAnnotationCollector<'tcx> {
+    type NestedFilter = OnlyBodies;
+    fn nested_visit_map(&mut self) -> Self::Map {
+        self.tcx.hir()
+    }
+
+    fn visit_stmt(&mut self, stmt: &'tcx Stmt<'tcx>) {
+        // Attributes of item statements belong to the items, which are selected on their own.
+        if !matches!(stmt.kind, StmtKind::Item(..)) {
+            let attrs = self.tcx.hir().attrs(stmt.hir_id);
+            if let Some(annotation) = Annotation::from_call_site_attrs(attrs, self.tcx) {
+                self.annotations.push((stmt.span, annotation));
+            }
+        }
+        intravisit::walk_stmt(self, stmt);
+    }
+}
+
+struct InstantiationCollector<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'a Body<'tcx>,
+    generic_fns: &'a HashSet<DefId>,
+    call_site_annotations: &'a Vec<(Span, Annotation)>,
+    instances: Vec<(ty::Instance<'tcx>, Option<Annotation>)>,
+}
 
-                // Retrieve the instance, as we know it exists.
-                Some((ty::Instance::mono(tcx, def_id), annotated_pure))
-            } else {
-                None
+impl<'a, 'tcx> Visitor<'tcx> for InstantiationCollector<'a, 'tcx> {
+    fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, _: Location) {
+        if let TerminatorKind::Call { func, .. } = &terminator.kind {
+            let func_ty = func.ty(self.body, self.tcx);
+            if let ty::TyKind::FnDef(def_id, substs) = func_ty.kind() {
+                // Only concrete instantiations can be analyzed.
+                if substs.has_param() {
+                    return;
+                }
+                // Resolve first, as calls to trait methods can land in generic impls.
+                let instance = ty::Instance::resolve(
+                    self.tcx,
+                    ty::ParamEnv::reveal_all(),
+                    def_id.to_owned(),
+                    self.tcx.erase_regions(substs),
+                )
+                .ok()
+                .flatten();
+                if let Some(instance) = instance {
+                    if let ty::InstanceDef::Item(..) = instance.def {
+                        if self.generic_fns.contains(&instance.def_id()) {
+                            let span = terminator.source_info.span;
+                            let annotation = self
+                                .call_site_annotations
+                                .iter()
+                                .find(|(stmt_span, ..)| stmt_span.contains(span))
+                                .map(|(.., annotation)| annotation.to_owned());
+                            self.instances.push((instance, annotation));
+                        }
+                    }
+                }
             }
+        }
+    }
+}
+
+// Find all concrete instantiations of generic functions that occur in the bodies of local items,
+// together with the annotation of the statement they occur in, if any.
+fn collect_instantiations<'tcx>(
+    generic_fns: &HashSet<DefId>,
+    tcx: TyCtxt<'tcx>,
+) -> Vec<(ty::Instance<'tcx>, Option<Annotation>)> {
+    let mut call_site_annotation_collector = CallSiteAnnotationCollector {
+        tcx,
+        annotations: vec![],
+    };
+    tcx.hir()
+        .visit_all_item_likes_in_crate(&mut call_site_annotation_collector);
+
+    let mut seen = HashSet::new();
+    tcx.mir_keys(())
+        .iter()
+        .filter(
+            |local_def_id| match tcx.hir().body_const_context(**local_def_id) {
+                Some(ConstContext::ConstFn) | None => true,
+                Some(_) => false,
+            },
+        )
+        .map(|local_def_id| {
+            let body = tcx.optimized_mir(local_def_id.to_def_id());
+            let mut instantiation_collector = InstantiationCollector {
+                tcx,
+                body,
+                generic_fns,
+                call_site_annotations: &call_site_annotation_collector.annotations,
+                instances: vec![],
+            };
+            instantiation_collector.visit_body(body);
+            instantiation_collector.instances
         })
+        .flatten()
+        .filter(|(instance, ..)| seen.insert(instance.to_owned()))
         .collect()
 }
+
+// Retrieve all functions with bodies: free functions, methods and default trait methods.
+fn functions_with_bodies(tcx: TyCtxt) -> Vec<DefId> {
+    let hir = tcx.hir();
+    let crate_items = tcx.hir_crate_items(());
+    let free_fns = crate_items.items().filter_map(|item_id| {
+        if let ItemKind::Fn(..) = hir.item(item_id).kind {
+            Some(item_id.owner_id.to_def_id())
+        } else {
+            None
+        }
+    });
+    let impl_fns = crate_items.impl_items().filter_map(|impl_item_id| {
+        if let ImplItemKind::Fn(..) = hir.impl_item(impl_item_id).kind {
+            Some(impl_item_id.owner_id.to_def_id())
+        } else {
+            None
+        }
+    });
+    let trait_fns = crate_items.trait_items().filter_map(|trait_item_id| {
+        if let TraitItemKind::Fn(_, TraitFn::Provided(..)) = hir.trait_item(trait_item_id).kind {
+            Some(trait_item_id.owner_id.to_def_id())
+        } else {
+            None
+        }
+    });
+    // Functions generated by macros of other crates, e.g. the initializers of `thread_local!`,
+    // cannot carry annotations.
+    free_fns
+        .chain(impl_fns)
+        .chain(trait_fns)
+        .filter(|def_id| !in_external_macro(tcx.sess, tcx.def_span(*def_id)))
+        .collect()
+}
+
+pub fn select_functions<'tcx>(tcx: TyCtxt<'tcx>) -> Vec<(ty::Instance<'tcx>, Annotation)> {
+    let mut generic_annotations = HashMap::new();
+
+    let mut selected: Vec<_> = functions_with_bodies(tcx)
+        .into_iter()
+        .filter_map(|def_id| {
+            let annotation = Annotation::from_attrs(def_id, tcx);
+
+            // Sanity check for generics, including the ones of the parent impl or trait.
+            let has_generics =
+                ty::GenericArgs::identity_for_item(tcx, def_id)
+                    .iter()
+                    .any(|param| match param.unpack() {
+                        ty::GenericArgKind::Lifetime(..) => false,
+                        ty::GenericArgKind::Type(..) | ty::GenericArgKind::Const(..) => true,
+                    });
+
+            if has_generics {
+                // Generic functions are analyzed per instantiation, see below.
+                generic_annotations.insert(def_id, annotation);
+                return None;
+            }
+
+            // Retrieve the instance, as we know it exists.
+            Some((ty::Instance::mono(tcx, def_id), annotation))
+        })
+        .collect();
+
+    let generic_fns = HashSet::from_iter(generic_annotations.keys().cloned());
+    selected.extend(collect_instantiations(&generic_fns, tcx).into_iter().map(
+        |(instance, annotation)| {
+            let annotation =
+                annotation.unwrap_or_else(|| generic_annotations[&instance.def_id()].to_owned());
+            (instance, annotation)
+        },
+    ));
+    selected
+}
@@ -1,5 +1,7 @@
+mod annotation;
 mod function;
 mod ppr;
 
+pub use annotation::Annotation;
 pub use function::select_functions;
 pub use ppr::select_pprs;
@@ -3,6 +3,8 @@ use rustc_middle::mir::visit::Visitor;
 use rustc_middle::mir::{Body, Location, Terminator, TerminatorKind};
 use rustc_middle::ty::{self, Ty, TyCtxt};
 
+use crate::selector::annotation::Annotation;
+
 struct PPRCollector<'tcx> {
     tcx: TyCtxt<'tcx>,
     body: Body<'tcx>,
@@ -39,7 +41,7 @@ impl<'tcx> Visitor<'tcx> for PPRCollector<'tcx> {
     }
 }
 
-pub fn select_pprs<'tcx>(tcx: TyCtxt<'tcx>) -> Vec<(ty::Instance<'tcx>, bool)> {
+pub fn select_pprs<'tcx>(tcx: TyCtxt<'tcx>) -> Vec<(ty::Instance<'tcx>, Annotation)> {
     tcx.mir_keys(())
         .iter()
         .map(
@@ -60,7 +62,7 @@ pub fn select_pprs<'tcx>(tcx: TyCtxt<'tcx>) -> Vec<(ty::Instance<'tcx>, bool)> {
                                         def_id.to_owned(),
                                         substs_ref,
                                     ),
-                                    true,
+                                    Annotation::pure(),
                                 )
                             } else {
                                 panic!("passed a non-closure to ppr constructor");
@@ -0,0 +1,120 @@
+use rustc_middle::ty::TyCtxt;
+use scrutils::{readable_path, PurityAnalysisResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use crate::output::write_atomically;
+
+// An accepted inconsistency: the region, identified by its readable path (e.g.
+// `tests::leaky::network::send`), and the code of the reason it failed for, if any (regions
+// annotated as impure that were verified pure have none).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BaselineEntry {
+    region: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+impl BaselineEntry {
+    pub fn from_result<'tcx>(result: &PurityAnalysisResult<'tcx>, tcx: TyCtxt<'tcx>) -> Self {
+        BaselineEntry {
+            region: readable_path(result.def_id(), tcx),
+            reason: result.reason().map(|reason| reason.code().to_owned()),
+        }
+    }
+
+    pub fn region(&self) -> &String {
+        &self.region
+    }
+}
+
+// Inconsistencies that are known and accepted, so that only regressions are reported.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Baseline {
+    accepted: BTreeSet<BaselineEntry>,
+}
+
+impl Baseline {
+    // A missing baseline file is treated as an empty baseline.
+    pub fn load(path: &str) -> Self {
+        match fs::read_to_string(path) {
+            Ok(data) => serde_json::from_str(&data)
+                .unwrap_or_else(|err| panic!("malformed baseline file {:?}: {}", path, err)),
+            Err(..) => Baseline::default(),
+        }
+    }
+
+    pub fn from_results<'tcx>(
+        results: &Vec<PurityAnalysisResult<'tcx>>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Self {
+        Baseline {
+            accepted: results
+                .iter()
+                .filter(|result| result.is_inconsistent())
+                .map(|result| BaselineEntry::from_result(result, tcx))
+                .collect(),
+        }
+    }
+
+    // Record the current inconsistencies, keeping the entries of regions that were not analyzed
+    // in this run, e.g. because of `target_filter`.
+    pub fn updated<'tcx>(
+        &self,
+        results: &Vec<PurityAnalysisResult<'tcx>>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Self {
+        let analyzed = analyzed_regions(results, tcx);
+        let current = Baseline::from_results(results, tcx);
+        Baseline {
+            accepted: self
+                .accepted
+                .iter()
+                .filter(|entry| !analyzed.contains(entry.region()))
+                .cloned()
+                .chain(current.accepted)
+                .collect(),
+        }
+    }
+
+    pub fn write(&self, path: &str) {
+        write_atomically(
+            Path::new(path),
+            &serde_json::to_string_pretty(self).unwrap(),
+        );
+    }
+
+    pub fn accepts<'tcx>(&self, result: &PurityAnalysisResult<'tcx>, tcx: TyCtxt<'tcx>) -> bool {
+        self.accepted
+            .contains(&BaselineEntry::from_result(result, tcx))
+    }
+
+    // Accepted inconsistencies of analyzed regions that no longer occur in the results. The
+    // results need to include consistent regions, so that they are not mistaken for unanalyzed
+    // ones.
+    pub fn fixed<'tcx>(
+        &self,
+        results: &Vec<PurityAnalysisResult<'tcx>>,
+        tcx: TyCtxt<'tcx>,
+    ) -> Vec<&BaselineEntry> {
+        let analyzed = analyzed_regions(results, tcx);
+        let current = Baseline::from_results(results, tcx);
+        self.accepted
+            .iter()
+            .filter(|entry| analyzed.contains(entry.region()))
+            .filter(|entry| !current.accepted.contains(entry))
+            .collect()
+    }
+}
+
+fn analyzed_regions<'tcx>(
+    results: &Vec<PurityAnalysisResult<'tcx>>,
+    tcx: TyCtxt<'tcx>,
+) -> BTreeSet<String> {
+    results
+        .iter()
+        .map(|result| readable_path(result.def_id(), tcx))
+        .collect()
+}
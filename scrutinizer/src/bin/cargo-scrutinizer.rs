@@ -1,5 +1,7 @@
 use env_logger::Target;
+use std::env;
 use std::fs::OpenOptions;
+use std::process::exit;
 
 fn main() {
     env_logger::builder()
@@ -11,5 +13,15 @@ fn main() {
                 .unwrap(),
         )))
         .init();
+
+    // `cargo scrutinizer diff <old> <new>` compares two result files without running the analysis.
+    let args = env::args()
+        .skip(1)
+        .skip_while(|arg| arg == "scrutinizer")
+        .collect::<Vec<_>>();
+    if args.first().map(|arg| arg == "diff").unwrap_or(false) {
+        exit(scrutinizer::diff_main(args.into_iter()));
+    }
+
     rustc_plugin::cli_main(scrutinizer::ScrutinizerPlugin);
 }
@@ -0,0 +1,230 @@
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_span::def_id::DefId;
+use scrutils::PurityAnalysisResult;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use crate::output::write_atomically;
+
+struct Node {
+    failing: bool,
+    allowlisted: bool,
+    no_important_locals: bool,
+    raw_pointer_deref: bool,
+    mutability_laundering: bool,
+    has_interior_mutation: bool,
+    global_state_write: bool,
+    has_body: bool,
+}
+
+impl Node {
+    // Same labels and colors as the ones used by `generate_graph.py`.
+    fn label_and_color(&self, name: &str) -> (String, &'static str) {
+        if self.failing {
+            let mut label = name.to_owned();
+            let mut fillcolor = "tomato";
+            if self.raw_pointer_deref {
+                label += " -R";
+                fillcolor = "orange";
+            }
+            if self.mutability_laundering {
+                label += " -T";
+                fillcolor = "orange";
+            }
+            if self.has_interior_mutation {
+                label += " -M";
+                fillcolor = "orange";
+            }
+            if self.global_state_write {
+                label += " -G";
+                fillcolor = "orange";
+            }
+            if !self.has_body {
+                label += " -B";
+                fillcolor = "orange";
+            }
+            (label, fillcolor)
+        } else if self.allowlisted {
+            (format!("{} -A", name), "orchid")
+        } else if self.no_important_locals {
+            (format!("{} -I", name), "khaki")
+        } else {
+            (name.to_owned(), "lightgreen")
+        }
+    }
+}
+
+// Call graph of a single region, with functions identified by their (instantiated) path and
+// edges labeled with the indices of the important arguments passed to the callee.
+struct CallGraph {
+    region: String,
+    nodes: BTreeMap<String, Node>,
+    edges: BTreeMap<(String, String), BTreeSet<usize>>,
+}
+
+fn function_name<'tcx>(
+    instance: Option<ty::Instance<'tcx>>,
+    def_id: DefId,
+    tcx: TyCtxt<'tcx>,
+) -> String {
+    match instance {
+        Some(instance) => format!("{}", instance),
+        None => tcx.def_path_str(def_id),
+    }
+}
+
+impl CallGraph {
+    fn build<'tcx>(result: &PurityAnalysisResult<'tcx>, tcx: TyCtxt<'tcx>) -> Self {
+        let mut nodes = BTreeMap::new();
+        let mut callers = vec![];
+        // Failing functions are inserted last, so they take precedence.
+        let functions = result
+            .passing()
+            .iter()
+            .map(|info| (info, false))
+            .chain(result.failing().iter().map(|info| (info, true)));
+        for (info, failing) in functions {
+            let name = function_name(info.function().instance(), info.function().def_id(), tcx);
+            nodes.insert(
+                name.clone(),
+                Node {
+                    failing,
+                    allowlisted: info.allowlisted(),
+                    no_important_locals: info.important_locals().is_empty(),
+                    raw_pointer_deref: info.raw_pointer_deref(),
+                    mutability_laundering: info.has_mutability_laundering(),
+                    has_interior_mutation: info.has_interior_mutation(),
+                    global_state_write: info.global_state_write(),
+                    has_body: info.function().instance().is_some(),
+                },
+            );
+            // Callees of allowlisted functions or functions without important locals are
+            // never analyzed.
+            if !info.allowlisted() && !info.important_locals().is_empty() {
+                callers.push((name, info));
+            }
+        }
+
+        let mut edges: BTreeMap<(String, String), BTreeSet<usize>> = BTreeMap::new();
+        for (caller, info) in callers {
+            for call in info.function().calls().into_iter().flatten() {
+                let important_args = info.important_locals().important_args_to_callee(
+                    call.args(),
+                    call.def_id(),
+                    tcx,
+                );
+                let callee = function_name(call.instance(), call.def_id(), tcx);
+                if nodes.contains_key(&callee) {
+                    edges
+                        .entry((caller.clone(), callee))
+                        .or_default()
+                        .extend(important_args);
+                }
+            }
+        }
+
+        CallGraph {
+            region: format!("{}", result.instance()),
+            nodes,
+            edges,
+        }
+    }
+
+    fn to_dot(&self) -> String {
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut dot = format!("digraph \"{}\" {{\n", escape(&self.region));
+        for (name, node) in self.nodes.iter() {
+            let (label, fillcolor) = node.label_and_color(name);
+            dot += &format!(
+                "    \"{}\" [label=\"{}\", fillcolor=\"{}\", style=\"filled\"];\n",
+                escape(name),
+                escape(&label),
+                fillcolor
+            );
+        }
+        for ((caller, callee), important_args) in self.edges.iter() {
+            let label = important_args
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            dot += &format!(
+                "    \"{}\" -> \"{}\" [label=\"{}\"];\n",
+                escape(caller),
+                escape(callee),
+                label
+            );
+        }
+        dot += "}\n";
+        dot
+    }
+
+    // Node-link format, as read by `networkx.node_link_graph`.
+    fn to_json(&self) -> Value {
+        let nodes: Vec<_> = self
+            .nodes
+            .iter()
+            .map(|(name, node)| {
+                let (label, fillcolor) = node.label_and_color(name);
+                json!({
+                    "id": name,
+                    "label": label,
+                    "fillcolor": fillcolor,
+                    "failing": node.failing,
+                    "allowlisted": node.allowlisted,
+                    "no_important_locals": node.no_important_locals,
+                    "raw_pointer_deref": node.raw_pointer_deref,
+                    "mutability_laundering": node.mutability_laundering,
+                    "has_interior_mutation": node.has_interior_mutation,
+                    "global_state_write": node.global_state_write,
+                    "has_body": node.has_body,
+                })
+            })
+            .collect();
+        let links: Vec<_> = self
+            .edges
+            .iter()
+            .map(|((caller, callee), important_args)| {
+                json!({
+                    "source": caller,
+                    "target": callee,
+                    "important_args": important_args,
+                })
+            })
+            .collect();
+        json!({
+            "directed": true,
+            "multigraph": false,
+            "graph": { "region": self.region },
+            "nodes": nodes,
+            "links": links,
+        })
+    }
+}
+
+// Write the call graph of every region into `<dir>/<region>.callgraph.{dot,json}`.
+pub fn write_callgraphs<'tcx>(
+    results: &Vec<PurityAnalysisResult<'tcx>>,
+    dir: &Path,
+    tcx: TyCtxt<'tcx>,
+) {
+    fs::create_dir_all(dir).unwrap();
+    for result in results.iter() {
+        let callgraph = CallGraph::build(result, tcx);
+        let file_stem: String = callgraph
+            .region
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        write_atomically(
+            &dir.join(format!("{}.callgraph.dot", file_stem)),
+            &callgraph.to_dot(),
+        );
+        write_atomically(
+            &dir.join(format!("{}.callgraph.json", file_stem)),
+            &serde_json::to_string_pretty(&callgraph.to_json()).unwrap(),
+        );
+    }
+}
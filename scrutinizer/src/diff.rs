@@ -0,0 +1,176 @@
+use clap::Parser;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+
+// Arguments of `cargo scrutinizer diff`.
+#[derive(Parser)]
+#[command(about = "Compare two analysis result files")]
+pub struct DiffArgs {
+    /// Results of the previous run.
+    old: String,
+    /// Results of the current run.
+    new: String,
+}
+
+// The parts of a serialized region that are compared between runs.
+struct Region {
+    status: bool,
+    // Whether each function reached from the region passed; a function that failed for any of its
+    // important arguments counts as failing.
+    functions: BTreeMap<String, bool>,
+    unhandled: BTreeSet<(String, String)>,
+    deps: BTreeMap<String, String>,
+}
+
+impl Region {
+    fn from_value(region: &Value) -> Self {
+        let mut functions = BTreeMap::new();
+        let mut unhandled = BTreeSet::new();
+        for (key, passing) in [("passing", true), ("failing", false)] {
+            for info in region[key].as_array().into_iter().flatten() {
+                let function = &info["function"];
+                // Prefer the readable path, which is stable across compilations.
+                let def_id = function["name"]
+                    .get("path")
+                    .map(string_of)
+                    .unwrap_or_else(|| string_of(&function["def_id"]));
+                let entry = functions.entry(def_id.clone()).or_insert(passing);
+                *entry = *entry && passing;
+                for ty in function["unhandled"].as_array().into_iter().flatten() {
+                    unhandled.insert((def_id.clone(), string_of(ty)));
+                }
+            }
+        }
+        let deps = region["deps"]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(krate, tree)| (krate.to_owned(), string_of(tree)))
+            .collect();
+        Region {
+            status: region["status"].as_bool().unwrap_or(false),
+            functions,
+            unhandled,
+            deps,
+        }
+    }
+}
+
+fn string_of(value: &Value) -> String {
+    value.as_str().map(str::to_owned).unwrap_or_default()
+}
+
+fn status_str(status: bool) -> &'static str {
+    if status {
+        "pure"
+    } else {
+        "impure"
+    }
+}
+
+// Read the regions of an `Output` document, keyed by their instance.
+fn read_regions(path: &str) -> BTreeMap<String, Region> {
+    let data = fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("failed to read results from {:?}: {}", path, err));
+    let output: Value = serde_json::from_str(&data)
+        .unwrap_or_else(|err| panic!("malformed results file {:?}: {}", path, err));
+    output["results"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|region| {
+            let key = region
+                .get("instance")
+                .or_else(|| region.get("def_id"))
+                .map(string_of)
+                .unwrap_or_default();
+            (key, Region::from_value(region))
+        })
+        .collect()
+}
+
+fn diff_region(name: &str, old: &Region, new: &Region) -> Vec<String> {
+    let mut changes = vec![];
+    if old.status != new.status {
+        changes.push(format!(
+            "`{}` changed from {} to {}",
+            name,
+            status_str(old.status),
+            status_str(new.status)
+        ));
+    }
+    for (function, new_passing) in new.functions.iter() {
+        if let Some(old_passing) = old.functions.get(function) {
+            if old_passing != new_passing {
+                changes.push(format!(
+                    "`{}`: `{}` moved from {} to {}",
+                    name,
+                    function,
+                    if *old_passing { "passing" } else { "failing" },
+                    if *new_passing { "passing" } else { "failing" }
+                ));
+            }
+        }
+    }
+    for (function, ty) in new.unhandled.difference(&old.unhandled) {
+        changes.push(format!(
+            "`{}`: new unhandled call of type `{}` in `{}`",
+            name, ty, function
+        ));
+    }
+    for (krate, tree) in new.deps.iter() {
+        match old.deps.get(krate) {
+            None => changes.push(format!("`{}`: new dependency `{}`", name, krate)),
+            Some(old_tree) if old_tree != tree => {
+                changes.push(format!("`{}`: dependency `{}` changed", name, krate))
+            }
+            _ => {}
+        }
+    }
+    for krate in old
+        .deps
+        .keys()
+        .filter(|krate| !new.deps.contains_key(*krate))
+    {
+        changes.push(format!("`{}`: removed dependency `{}`", name, krate));
+    }
+    changes
+}
+
+// Compare two analysis results and print every change. Returns whether any region that was pure
+// before is no longer pure.
+pub fn diff(args: &DiffArgs) -> bool {
+    let old_regions = read_regions(&args.old);
+    let new_regions = read_regions(&args.new);
+
+    let mut regressed = false;
+    for (name, new) in new_regions.iter() {
+        match old_regions.get(name) {
+            Some(old) => {
+                regressed |= old.status && !new.status;
+                for change in diff_region(name, old, new) {
+                    println!("{}", change);
+                }
+            }
+            None => println!("`{}` is new and {}", name, status_str(new.status)),
+        }
+    }
+    for name in old_regions
+        .keys()
+        .filter(|name| !new_regions.contains_key(*name))
+    {
+        println!("`{}` is no longer analyzed", name);
+    }
+    regressed
+}
+
+// Entry point of `cargo scrutinizer diff <old> <new>`, where `args` start with `diff`.
+pub fn diff_main(args: impl Iterator<Item = String>) -> i32 {
+    let args = DiffArgs::parse_from(args);
+    if diff(&args) {
+        1
+    } else {
+        0
+    }
+}
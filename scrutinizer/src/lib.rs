@@ -14,12 +14,25 @@ extern crate rustc_session;
 extern crate rustc_span;
 extern crate rustc_trait_selection;
 
+mod baseline;
+mod callgraph;
+mod diff;
+mod output;
+mod report;
+mod sarif;
+
+use baseline::Baseline;
+use output::{output_path, write_atomically};
+use report::Summary;
+
+pub use diff::diff_main;
+
 use scrutils::{
-    dump_mir_and_borrowck_facts, substituted_mir, precheck, run_analysis, select_functions,
-    select_pprs, Collector, ImportantLocals, PurityAnalysisResult,
+    dump_mir_and_borrowck_facts, fnv1a, mut_ref_args, readable_path, substituted_mir, precheck,
+    run_analysis, resolve_important_args, select_functions, select_pprs, Annotation, Collector,
+    FailureReason, FfiSummary, ImportantArg, PurityAnalysisResult, RawPtrHeuristic, VerdictCache,
 };
 
-use chrono::offset::Local;
 use clap::Parser;
 use regex::Regex;
 use rustc_middle::ty;
@@ -29,10 +42,9 @@ use rustc_span::def_id::LOCAL_CRATE;
 use rustc_utils::mir::borrowck_facts;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::fs;
-use std::fs::File;
-use std::io::Write;
 use std::process::{exit, Command};
 use std::time::Instant;
 
@@ -57,10 +69,38 @@ fn default_output_file() -> String {
     "analysis.result.json".to_string()
 }
 
+fn default_timestamp_output() -> bool {
+    false
+}
+
 fn default_shallow() -> bool {
     false
 }
 
+fn default_summaries() -> bool {
+    false
+}
+
+fn default_enumerate_implementors() -> bool {
+    false
+}
+
+fn default_allow_mut_args() -> bool {
+    false
+}
+
+fn default_raw_ptr_heuristic() -> RawPtrHeuristic {
+    RawPtrHeuristic::Coarse
+}
+
+fn default_summary_by_module() -> bool {
+    false
+}
+
+fn default_update_baseline() -> bool {
+    false
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Config {
     #[serde(default = "default_mode")]
@@ -69,13 +109,61 @@ pub struct Config {
     only_inconsistent: bool,
     #[serde(default = "default_output_file")]
     output_file: String,
+    // Directory for all output files given by relative paths, defaults to the crate root.
+    output_dir: Option<String>,
+    // Whether to prefix the name of the results file with the time of the run.
+    #[serde(default = "default_timestamp_output")]
+    timestamp_output: bool,
+    // If set, the results are additionally written in the SARIF format to this file.
+    sarif_file: Option<String>,
+    // If set, the call graph of every region is written to this directory in the DOT and the
+    // node-link JSON formats.
+    callgraph_dir: Option<String>,
+    // Whether the summary of the run additionally breaks down the regions per module.
+    #[serde(default = "default_summary_by_module")]
+    summary_by_module: bool,
+    // If set, inconsistencies recorded in this file are accepted and only new ones are reported.
+    baseline_file: Option<String>,
+    // Whether to record all current inconsistencies in the baseline file instead.
+    #[serde(default = "default_update_baseline")]
+    update_baseline: bool,
     #[serde(default = "default_shallow")]
     shallow: bool,
+    // Whether to save the analysis of function bodies next to the artifacts of the analyzed crate
+    // and reuse it in later runs. The call graph of every region is still collected on each run.
+    #[serde(default = "default_summaries")]
+    summaries: bool,
+    #[serde(default = "default_enumerate_implementors")]
+    enumerate_implementors: bool,
+    #[serde(default = "default_allow_mut_args")]
+    allow_mut_args: bool,
+    // Either "coarse", which fails functions on any write through a `*mut`, or "dataflow", which
+    // only fails them if important data is written to memory visible outside of the function.
+    #[serde(default = "default_raw_ptr_heuristic")]
+    raw_ptr_heuristic: RawPtrHeuristic,
 
     target_filter: Option<String>,
+    // Regions whose def path contains any of these are not analyzed, e.g. the ones that are only
+    // checked under a different configuration.
+    exclude_filter: Option<Vec<String>>,
+    // Important arguments of every region as MIR locals, i.e. starting from 1, since local 0 is
+    // the return place.
     important_args: Option<Vec<usize>>,
+    // Important parameters of individual functions, either by position or by name. Unlike
+    // `important_args`, positions are parameter indices starting from 0, as in
+    // `#[scrutinizer::important(..)]`, and `self` counts as a parameter. Functions are keyed by
+    // their full def path without the crate name and without trimming or re-exports, e.g.
+    // `"module::foo" = [0, "secret"]` for a free function and `"module::Type::method" = [1]` for
+    // an inherent method.
+    important_params: Option<HashMap<String, Vec<ImportantArg>>>,
+    // Regexes matched against both the debug format of `DefId`s, e.g.
+    // `core[e1a5]::intrinsics::{extern#0}::likely`, and readable paths, e.g.
+    // `core::intrinsics::likely`.
     allowlist: Option<Vec<String>>,
     trusted_stdlib: Option<Vec<String>>,
+    // Purity of foreign functions keyed by their link name or def path, e.g. `"my_hash" = "pure"`,
+    // overriding the built-in classification of libc and libm functions.
+    ffi_summaries: Option<BTreeMap<String, FfiSummary>>,
 }
 
 enum CrateHandling {
@@ -210,6 +298,7 @@ struct Output<'tcx> {
     results: Vec<PurityAnalysisResult<'tcx>>,
     elapsed: f32,
     crate_name: String,
+    summary: Option<Summary>,
 }
 
 impl rustc_driver::Callbacks for ScrutinizerCallbacks {
@@ -241,26 +330,83 @@ impl rustc_driver::Callbacks for ScrutinizerCallbacks {
             let results = scrutinizer(tcx, &self.args);
             let elapsed = now.elapsed();
 
+            // The baseline is compared against all analyzed regions, including the consistent
+            // ones that `only_inconsistent` leaves out of the output.
+            let baseline = self.args.baseline_file.as_ref().map(|baseline_file| {
+                let baseline = Baseline::load(baseline_file);
+                if self.args.update_baseline {
+                    let baseline = baseline.updated(&results, tcx);
+                    baseline.write(baseline_file);
+                    println!("Scrutinizer updated the baseline in {:?}.", baseline_file);
+                    baseline
+                } else {
+                    for entry in baseline.fixed(&results, tcx) {
+                        println!(
+                            "Scrutinizer no longer reports the baseline entry for `{}`, consider removing it.",
+                            entry.region()
+                        );
+                    }
+                    baseline
+                }
+            });
+
+            // Like the baseline, the summary counts all analyzed regions.
+            let summary = Summary::from_results(
+                &results,
+                elapsed.as_secs_f32(),
+                self.args.summary_by_module,
+                tcx,
+            );
+            let crate_name = format!("{}", tcx.crate_name(LOCAL_CRATE));
+            summary.print(&crate_name);
+
+            let results = results
+                .into_iter()
+                .filter(|result| !self.args.only_inconsistent || result.is_inconsistent())
+                .collect();
             let output = Output {
                 results,
                 elapsed: elapsed.as_secs_f32(),
-                crate_name: format!("{}", tcx.crate_name(LOCAL_CRATE))
-
+                crate_name,
+                summary: Some(summary),
             };
 
-            let output_string = serde_json::to_string_pretty(&output).unwrap();
-            let file_name = format!("{}.{}", Local::now(), self.args.output_file);
-            File::create(file_name)
-                .and_then(|mut file| file.write_all(output_string.as_bytes()))
-                .unwrap();
+            let output_dir = self.args.output_dir.as_ref();
+            let results_path = output_path(
+                output_dir,
+                &self.args.output_file,
+                self.args.timestamp_output,
+            );
+            write_atomically(&results_path, &serde_json::to_string_pretty(&output).unwrap());
+            if let Some(sarif_file) = self.args.sarif_file.as_ref() {
+                write_atomically(
+                    &output_path(output_dir, sarif_file, false),
+                    &serde_json::to_string_pretty(&sarif::to_sarif(&output.results, tcx)).unwrap(),
+                );
+            }
+            if let Some(callgraph_dir) = self.args.callgraph_dir.as_ref() {
+                callgraph::write_callgraphs(
+                    &output.results,
+                    &output_path(output_dir, callgraph_dir, false),
+                    tcx,
+                );
+            }
+
+            // Inconsistencies accepted by the baseline are not reported.
             let inconsistent: Vec<_> = output
                 .results
                 .iter()
                 .filter(|res| res.is_inconsistent())
-                .map(|res| res.def_id())
+                .filter(|res| {
+                    baseline
+                        .as_ref()
+                        .map(|baseline| !baseline.accepts(res, tcx))
+                        .unwrap_or(true)
+                })
                 .collect();
+            report_inconsistent(&inconsistent, tcx);
             if !inconsistent.is_empty() {
-                println!("Scrutinizer failed to verify the purity of the following regions: {:?}. See more information in {:?}.", inconsistent, self.args.output_file);
+                println!("Scrutinizer failed to verify the purity of {} regions. See more information in {:?}.", inconsistent.len(), results_path);
                 exit(-1);
             }
         });
@@ -269,6 +415,68 @@ impl rustc_driver::Callbacks for ScrutinizerCallbacks {
     }
 }
 
+// Report inconsistent regions as compiler diagnostics pointing at the region, with labels for the
+// chain of calls leading to the first failing function.
+fn report_inconsistent<'tcx>(
+    results: &Vec<&PurityAnalysisResult<'tcx>>,
+    tcx: ty::TyCtxt<'tcx>,
+) {
+    for result in results.iter() {
+        let def_id = result.def_id();
+        let span = tcx.def_span(def_id);
+        if !result.annotated_pure() {
+            tcx.sess
+                .struct_span_warn(
+                    span,
+                    format!(
+                        "`{}` is annotated as impure, but scrutinizer verified its purity",
+                        tcx.def_path_str(def_id)
+                    ),
+                )
+                .emit();
+            continue;
+        }
+
+        let mut diagnostic = tcx.sess.struct_span_err(
+            span,
+            format!(
+                "scrutinizer failed to verify the purity of `{}`",
+                tcx.def_path_str(def_id)
+            ),
+        );
+        if let Some(reason) = result.reason() {
+            diagnostic.note(format!("{} [{}]", reason, reason.code()));
+        }
+
+        let chain = result.failure_chain();
+        for (depth, info) in chain.iter().enumerate().skip(1) {
+            let callee_def_id = info.function().def_id();
+            let label = if depth == chain.len() - 1 {
+                let cause = info
+                    .failure_reasons()
+                    .iter()
+                    .map(|reason| reason.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    "{}: `{}` fails: {}",
+                    depth,
+                    tcx.def_path_str(callee_def_id),
+                    cause
+                )
+            } else {
+                format!(
+                    "{}: `{}` is on the failing call path",
+                    depth,
+                    tcx.def_path_str(callee_def_id)
+                )
+            };
+            diagnostic.span_label(tcx.def_span(callee_def_id), label);
+        }
+        diagnostic.emit();
+    }
+}
+
 // The entry point of analysis.
 fn scrutinizer<'tcx>(tcx: ty::TyCtxt<'tcx>, args: &Config) -> Vec<PurityAnalysisResult<'tcx>> {
     let instances = if args.mode == "function" {
@@ -279,7 +487,14 @@ fn scrutinizer<'tcx>(tcx: ty::TyCtxt<'tcx>, args: &Config) -> Vec<PurityAnalysis
         panic!("undefined mode")
     };
 
-    instances
+    // Verdicts are shared between all regions of the crate.
+    let mut cache = if args.summaries {
+        VerdictCache::with_summaries(summary_fingerprint(args), tcx)
+    } else {
+        VerdictCache::new()
+    };
+
+    let results: Vec<_> = instances
         .into_iter()
         .filter(|(instance, _)| {
             args.target_filter.is_none()
@@ -287,15 +502,37 @@ fn scrutinizer<'tcx>(tcx: ty::TyCtxt<'tcx>, args: &Config) -> Vec<PurityAnalysis
                     .def_path_str(instance.def_id())
                     .contains(args.target_filter.as_ref().unwrap().as_str())
         })
-        .map(|(instance, annotated_pure)| analyze_instance(instance, annotated_pure, tcx, args))
-        .filter(|result| {
-            if args.only_inconsistent {
-                result.is_inconsistent()
-            } else {
-                true
-            }
+        .filter(|(instance, _)| {
+            let def_path = tcx.def_path_str(instance.def_id());
+            !args
+                .exclude_filter
+                .iter()
+                .flatten()
+                .any(|excluded| def_path.contains(excluded.as_str()))
+        })
+        .map(|(instance, annotation)| {
+            analyze_instance(instance, annotation, &mut cache, tcx, args)
         })
-        .collect()
+        .collect();
+
+    cache.persist_summaries(tcx);
+    results
+}
+
+// Summaries are only valid for the analyzer version and configuration they were computed with.
+// The fingerprint is persisted, so it needs a hash that is stable across Rust releases.
+fn summary_fingerprint(args: &Config) -> String {
+    let config = serde_json::to_string(&(
+        env!("CARGO_PKG_VERSION"),
+        args.shallow,
+        args.enumerate_implementors,
+        &args.allowlist,
+        &args.trusted_stdlib,
+        args.raw_ptr_heuristic,
+        &args.ffi_summaries,
+    ))
+    .unwrap();
+    format!("{:016x}", fnv1a(config.as_bytes()))
 }
 
 macro_rules! warn {
@@ -306,38 +543,62 @@ macro_rules! warn {
 
 fn analyze_instance<'tcx>(
     instance: ty::Instance<'tcx>,
-    annotated_pure: bool,
+    annotation: Annotation,
+    cache: &mut VerdictCache<'tcx>,
     tcx: ty::TyCtxt<'tcx>,
     args: &Config,
 ) -> PurityAnalysisResult<'tcx> {
-    warn!("\x1b[96mStarted analyzing {:?}\x1b[0m", &instance.def_id());
+    warn!("\x1b[96mStarted analyzing {}\x1b[0m", &instance);
 
-    let def_id = instance.def_id();
+    let annotated_pure = annotation.is_pure();
 
-    match precheck(instance, tcx) {
+    match precheck(instance, args.allow_mut_args, tcx) {
         Err(reason) => {
-            return PurityAnalysisResult::error(def_id, reason, annotated_pure);
+            return PurityAnalysisResult::error(instance, reason, annotated_pure);
         }
         _ => {}
     };
 
-    let collector = Collector::collect(instance, tcx, args.shallow);
-
-    // Calculate important locals.
-    let important_locals = {
-        // Parse important arguments.
-        let important_args = if args.important_args.is_none() {
-            // If no important arguments are provided, assume all are important.
-            let arg_count = {
-                let body = substituted_mir(&instance, tcx);
-                body.arg_count
-            };
-            (1..=arg_count).collect()
-        } else {
-            args.important_args.as_ref().unwrap().to_owned()
-        };
-        ImportantLocals::from_important_args(important_args, def_id, tcx)
+    let collector =
+        Collector::collect(instance, tcx, args.shallow, args.enumerate_implementors);
+
+    // Parse important arguments: the ones from the region annotation take precedence over the
+    // ones configured for the function, which take precedence over the global ones.
+    let function_important_args = annotation.important_args().or_else(|| {
+        args.important_params
+            .as_ref()
+            .and_then(|table| table.get(&readable_path(instance.def_id(), tcx)))
+    });
+    let important_args = match function_important_args {
+        Some(function_important_args) => {
+            match resolve_important_args(function_important_args, instance.def_id(), tcx) {
+                Ok(important_args) => Some(important_args),
+                Err(message) => {
+                    return PurityAnalysisResult::error(
+                        instance,
+                        FailureReason::InternalError(message),
+                        annotated_pure,
+                    );
+                }
+            }
+        }
+        None => args.important_args.to_owned(),
     };
+    let mut important_args = important_args.unwrap_or_else(|| {
+        // If no important arguments are provided, assume all are important.
+        let arg_count = {
+            let body = substituted_mir(&instance, tcx);
+            body.arg_count
+        };
+        (1..=arg_count).collect()
+    });
+    // Mutable arguments are output sinks, which become sensitive after the call, no matter
+    // whether the important arguments were listed or defaulted.
+    if args.allow_mut_args {
+        important_args.extend(mut_ref_args(instance, tcx));
+        important_args.sort();
+        important_args.dedup();
+    }
 
     let allowlist = args
         .allowlist
@@ -355,13 +616,18 @@ fn analyze_instance<'tcx>(
         .map(|re| Regex::new(re).unwrap())
         .collect();
 
+    let ffi_summaries = args.ffi_summaries.clone().unwrap_or_default();
+
     run_analysis(
         collector.get_function_info_storage(),
         collector.get_closure_info_storage(),
-        important_locals,
+        important_args,
         annotated_pure,
         &allowlist,
         &trusted_stdlib,
+        args.raw_ptr_heuristic,
+        &ffi_summaries,
+        cache,
         tcx,
     )
 }
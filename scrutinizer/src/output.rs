@@ -0,0 +1,40 @@
+use chrono::offset::Local;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// Resolve the path of an output file: relative paths are placed in the output directory, which
+// is created if it does not exist yet.
+pub fn output_path(output_dir: Option<&String>, file_name: &str, timestamp: bool) -> PathBuf {
+    let file_name = if timestamp {
+        format!("{}.{}", Local::now().format("%Y%m%d-%H%M%S"), file_name)
+    } else {
+        file_name.to_owned()
+    };
+    let path = match output_dir {
+        Some(output_dir) => Path::new(output_dir).join(file_name),
+        None => PathBuf::from(file_name),
+    };
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent).unwrap();
+    }
+    path
+}
+
+// Write the file next to its destination first and move it into place afterwards, so that readers
+// never observe a partially written file.
+pub fn write_atomically(path: &Path, contents: &str) {
+    let mut temp_name = path.file_name().unwrap().to_owned();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+    fs::File::create(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(contents.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&temp_path, path))
+        .unwrap_or_else(|err| panic!("failed to write {:?}: {}", path, err));
+}
@@ -0,0 +1,90 @@
+use rustc_middle::ty::TyCtxt;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use scrutils::PurityAnalysisResult;
+
+// Confusion matrix of annotated vs. determined purity. A false positive is a region annotated as
+// pure that was determined to be impure, and a false negative is the opposite.
+#[derive(Serialize, Default)]
+pub struct Counts {
+    true_pure: usize,
+    true_impure: usize,
+    false_positives: usize,
+    false_negatives: usize,
+    bodies_analyzed: usize,
+}
+
+impl Counts {
+    fn add(&mut self, annotated_pure: bool, status: bool, bodies: usize) {
+        match (annotated_pure, status) {
+            (true, true) => self.true_pure += 1,
+            (false, false) => self.true_impure += 1,
+            (true, false) => self.false_positives += 1,
+            (false, true) => self.false_negatives += 1,
+        }
+        self.bodies_analyzed += bodies;
+    }
+}
+
+#[derive(Serialize)]
+pub struct Summary {
+    #[serde(flatten)]
+    counts: Counts,
+    elapsed: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    modules: Option<BTreeMap<String, Counts>>,
+}
+
+impl Summary {
+    pub fn from_results<'tcx>(
+        results: &[PurityAnalysisResult<'tcx>],
+        elapsed: f32,
+        by_module: bool,
+        tcx: TyCtxt<'tcx>,
+    ) -> Self {
+        let mut counts = Counts::default();
+        let mut modules: BTreeMap<String, Counts> = BTreeMap::new();
+        for result in results.iter() {
+            let bodies = result.passing().len() + result.failing().len();
+            counts.add(result.annotated_pure(), result.status(), bodies);
+            if by_module {
+                let module = tcx.parent_module_from_def_id(result.def_id().expect_local());
+                let module_path = tcx.def_path_str(module.to_def_id());
+                modules
+                    .entry(if module_path.is_empty() {
+                        String::from("crate")
+                    } else {
+                        module_path
+                    })
+                    .or_default()
+                    .add(result.annotated_pure(), result.status(), bodies);
+            }
+        }
+        Summary {
+            counts,
+            elapsed,
+            modules: by_module.then_some(modules),
+        }
+    }
+
+    pub fn print(&self, crate_name: &str) {
+        println!("Crate analyzed: {}", crate_name);
+        println!("-----");
+        print_counts(&self.counts);
+        println!("Time taken (s):\t\t\t{}", self.elapsed);
+        for (module, counts) in self.modules.iter().flatten() {
+            println!("-----");
+            println!("Module: {}", module);
+            print_counts(counts);
+        }
+    }
+}
+
+fn print_counts(counts: &Counts) {
+    println!("Pure / Determined pure:\t\t{}", counts.true_pure);
+    println!("Impure / Determined impure:\t{}", counts.true_impure);
+    println!("Pure / Determined impure:\t{}", counts.false_positives);
+    println!("Impure / Determined pure:\t{}", counts.false_negatives);
+    println!("Bodies analyzed:\t\t{}", counts.bodies_analyzed);
+}
@@ -0,0 +1,208 @@
+use rustc_middle::ty::TyCtxt;
+use rustc_span::{FileName, RealFileName, Span};
+use scrutils::{FailureReason, ForeignCallKind, PurityAnalysisResult};
+use serde_json::{json, Value};
+use std::env;
+use std::path::{Component, Path};
+
+// Every failure reason is reported as a rule with its stable code as the id.
+fn rules() -> Vec<FailureReason> {
+    vec![
+        FailureReason::ErasedArgs,
+        FailureReason::MutableArgs,
+        FailureReason::UnhandledCallType,
+        FailureReason::RawPointerWrite,
+        FailureReason::MutableTransmute,
+        FailureReason::InteriorMutation,
+        FailureReason::GlobalStateWrite,
+        FailureReason::InlineAsm,
+        FailureReason::ForeignCall(ForeignCallKind::Unknown),
+        FailureReason::NoMirAvailable,
+        FailureReason::IntrinsicNotAllowlisted,
+        FailureReason::RecursionLimit,
+        FailureReason::LeakingCall,
+        FailureReason::LeakingImplementor(String::from("an implementor")),
+        FailureReason::InternalError(String::from("the analysis failed")),
+    ]
+}
+
+// Percent-encode everything but the unreserved characters of RFC 3986.
+fn encode_uri_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+// Join the encoded components of a path with slashes, leaving out the root.
+fn encode_path(path: &Path) -> String {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(segment) => Some(encode_uri_segment(&segment.to_string_lossy())),
+            Component::ParentDir => Some(String::from("..")),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// Files below the working directory, i.e. the crate being analyzed, are referenced relative to
+// `%SRCROOT%`, and other files on disk (e.g. dependencies) by an absolute `file` URI. Sources of
+// the standard library are remapped to `/rustc/<commit>/...` and referenced relative to
+// `RUST_SRC` instead. Other spans, e.g. of remapped dependencies, have no usable location.
+fn artifact_location(file_name: &FileName) -> Option<Value> {
+    let path = match file_name {
+        FileName::Real(RealFileName::LocalPath(path)) => path,
+        FileName::Real(RealFileName::Remapped { virtual_name, .. }) => {
+            let mut components = virtual_name.components();
+            return match (components.next(), components.next(), components.next()) {
+                (Some(Component::RootDir), Some(Component::Normal(rustc)), Some(_))
+                    if rustc == "rustc" =>
+                {
+                    Some(json!({
+                        "uri": encode_path(components.as_path()),
+                        "uriBaseId": "RUST_SRC",
+                    }))
+                }
+                _ => None,
+            };
+        }
+        _ => return None,
+    };
+    let relative_path = if path.is_absolute() {
+        env::current_dir()
+            .ok()
+            .and_then(|current_dir| path.strip_prefix(current_dir).ok())
+    } else {
+        Some(path.as_path())
+    };
+    Some(match relative_path {
+        Some(relative_path) => json!({
+            "uri": encode_path(relative_path),
+            "uriBaseId": "%SRCROOT%",
+        }),
+        None => json!({ "uri": format!("file:///{}", encode_path(path)) }),
+    })
+}
+
+fn physical_location(span: Span, tcx: TyCtxt) -> Option<Value> {
+    let source_map = tcx.sess.source_map();
+    let start = source_map.lookup_char_pos(span.lo());
+    let end = source_map.lookup_char_pos(span.hi());
+    artifact_location(&start.file.name).map(|artifact_location| {
+        json!({
+            "artifactLocation": artifact_location,
+            "region": {
+                "startLine": start.line,
+                "startColumn": start.col.0 + 1,
+                "endLine": end.line,
+                "endColumn": end.col.0 + 1,
+            },
+        })
+    })
+}
+
+fn sarif_result<'tcx>(result: &PurityAnalysisResult<'tcx>, tcx: TyCtxt<'tcx>) -> Value {
+    let def_id = result.def_id();
+    let region_location = physical_location(tcx.def_span(def_id), tcx);
+    let chain = result.failure_chain();
+
+    let rule_id = result
+        .reason()
+        .map(|reason| reason.code())
+        .unwrap_or(FailureReason::LeakingCall.code());
+
+    let thread_flow_locations: Vec<_> = chain
+        .iter()
+        .map(|info| {
+            let def_id = info.function().def_id();
+            let span = info.function().span().unwrap_or(tcx.def_span(def_id));
+            let mut location = json!({ "message": { "text": tcx.def_path_str(def_id) } });
+            if let Some(physical_location) = physical_location(span, tcx) {
+                location["physicalLocation"] = physical_location;
+            }
+            json!({ "location": location })
+        })
+        .collect();
+
+    // Regions that were expected to be impure are only reported for reference.
+    let level = if result.is_inconsistent() {
+        "error"
+    } else {
+        "note"
+    };
+
+    let mut sarif_result = json!({
+        "ruleId": rule_id,
+        "level": level,
+        "message": {
+            "text": format!(
+                "Scrutinizer failed to verify the purity of `{}`: {}",
+                tcx.def_path_str(def_id),
+                result
+                    .reason()
+                    .map(|reason| reason.to_string())
+                    .unwrap_or_default()
+            ),
+        },
+    });
+    if let Some(region_location) = region_location {
+        sarif_result["locations"] = json!([{ "physicalLocation": region_location }]);
+    }
+    if !thread_flow_locations.is_empty() {
+        sarif_result["codeFlows"] = json!([{
+            "threadFlows": [{ "locations": thread_flow_locations }],
+        }]);
+    }
+    sarif_result
+}
+
+// Produce a SARIF 2.1.0 log with one result per failing region, with inconsistent regions as
+// errors and the ones annotated as impure as notes.
+pub fn to_sarif<'tcx>(results: &Vec<PurityAnalysisResult<'tcx>>, tcx: TyCtxt<'tcx>) -> Value {
+    let rules: Vec<_> = rules()
+        .iter()
+        .map(|reason| {
+            json!({
+                "id": reason.code(),
+                "shortDescription": { "text": reason.to_string() },
+            })
+        })
+        .collect();
+    let sarif_results: Vec<_> = results
+        .iter()
+        .filter(|result| !result.status())
+        .map(|result| sarif_result(result, tcx))
+        .collect();
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "scrutinizer",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "originalUriBaseIds": {
+                "%SRCROOT%": {
+                    "uri": env::current_dir()
+                        .map(|current_dir| format!("file:///{}/", encode_path(&current_dir)))
+                        .unwrap_or_default(),
+                },
+                "RUST_SRC": {
+                    "description": {
+                        "text": "The root of the Rust toolchain sources, i.e. the parent of `library/`.",
+                    },
+                },
+            },
+            "results": sarif_results,
+        }],
+    })
+}
@@ -1,6 +1,9 @@
 #![feature(allocator_api)]
 #![feature(const_trait_impl)]
 #![feature(const_refs_to_cell)]
+#![feature(register_tool)]
+#![feature(thread_local)]
+#![register_tool(scrutinizer)]
 #![allow(dead_code, unused_variables)]
 
 mod collections;
@@ -0,0 +1,36 @@
+mod async_pure {
+    #[doc = "pure"]
+    async fn add_one(data: usize) -> usize {
+        data + 1
+    }
+
+    #[doc = "pure"]
+    pub async fn async_add(data: usize) -> usize {
+        data + 1
+    }
+
+    #[doc = "pure"]
+    pub async fn async_await_pure(data: usize) -> usize {
+        let incremented = add_one(data).await;
+        incremented * 2
+    }
+}
+
+mod async_leaky {
+    #[doc = "impure"]
+    async fn leak(data: usize) {
+        println!("{}", data);
+    }
+
+    #[doc = "impure"]
+    pub async fn async_leak(data: usize) -> usize {
+        println!("{}", data);
+        data
+    }
+
+    #[doc = "impure"]
+    pub async fn async_await_leak(data: usize) -> usize {
+        leak(data).await;
+        data
+    }
+}
@@ -0,0 +1,56 @@
+mod tool_attrs {
+    /// Adds one to the data, documented with a regular doc comment.
+    #[scrutinizer::pure]
+    pub fn documented_pure(data: usize) -> usize {
+        data + 1
+    }
+
+    /// Prints the data, documented with a regular doc comment.
+    #[scrutinizer::impure]
+    pub fn documented_impure(data: usize) -> usize {
+        println!("{}", data);
+        data
+    }
+
+    // Only the first argument is sensitive, the second one may be printed.
+    #[scrutinizer::pure]
+    #[scrutinizer::important(0)]
+    pub fn important_first(sensitive: usize, public: usize) -> usize {
+        println!("{}", public);
+        sensitive + public
+    }
+
+    #[scrutinizer::impure]
+    #[scrutinizer::important(1)]
+    pub fn important_second(public: usize, sensitive: usize) -> usize {
+        println!("{}", sensitive);
+        sensitive + public
+    }
+}
+
+mod important_by_name {
+    #[scrutinizer::pure]
+    #[scrutinizer::important(sensitive)]
+    pub fn important_named(public: usize, sensitive: usize) -> usize {
+        println!("{}", public);
+        sensitive + public
+    }
+
+    // Important arguments for this function are set in the configuration file.
+    #[scrutinizer::pure]
+    pub fn important_configured(sensitive: usize, public: usize) -> usize {
+        println!("{}", public);
+        sensitive + public
+    }
+
+    pub struct Vault;
+
+    impl Vault {
+        // Configured by the path of the method, i.e. the module path followed by the type.
+        #[scrutinizer::pure]
+        pub fn configured_method(&self, sensitive: usize, public: usize) -> usize {
+            println!("{}", public);
+            sensitive + public
+        }
+    }
+}
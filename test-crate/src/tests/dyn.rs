@@ -8,12 +8,14 @@ mod object_type_eraser {
     struct ImpureIncrementer;
 
     impl DynamicTrait for PureIncrementer {
+        #[doc = "pure"]
         fn inc(&self, a: usize) -> usize {
             a + 1
         }
     }
 
     impl DynamicTrait for ImpureIncrementer {
+        #[doc = "impure"]
         fn inc(&self, a: usize) -> usize {
             println!("{}", a);
             a + 1
@@ -30,10 +32,86 @@ mod object_type_eraser {
         dyn_eraser_helper(a, dynamic)
     }
 
+    // The receiver is erased before the call, so all implementors are analyzed and the region
+    // fails naming `ImpureIncrementer` as the leaking implementor.
     #[doc = "impure"]
     fn dyn_eraser_helper(a: usize, dynamic: &dyn DynamicTrait) -> usize {
         dynamic.inc(a)
     }
+
+    #[doc = "pure"]
+    fn make_incrementer(a: usize) -> Box<dyn DynamicTrait> {
+        if a == 0 {
+            Box::new(PureIncrementer {})
+        } else {
+            Box::new(ImpureIncrementer {})
+        }
+    }
+
+    // Same as above, with the receiver erased by the return type of another function.
+    #[doc = "impure"]
+    fn boxed_eraser(a: usize) -> usize {
+        make_incrementer(a).inc(a)
+    }
+}
+
+mod pure_implementors {
+    trait PureTrait {
+        fn double(&self, a: usize) -> usize;
+    }
+
+    struct Doubler;
+
+    struct AltDoubler;
+
+    impl PureTrait for Doubler {
+        #[doc = "pure"]
+        fn double(&self, a: usize) -> usize {
+            a * 2
+        }
+    }
+
+    impl PureTrait for AltDoubler {
+        #[doc = "pure"]
+        fn double(&self, a: usize) -> usize {
+            a + a
+        }
+    }
+
+    #[doc = "pure"]
+    fn erased_pure_helper(a: usize, dynamic: &dyn PureTrait) -> usize {
+        dynamic.double(a)
+    }
+}
+
+mod generic_implementors {
+    trait Scaler {
+        fn scale(&self, a: usize) -> usize;
+    }
+
+    struct Tripler;
+
+    struct Wrapper<T>(T);
+
+    impl Scaler for Tripler {
+        #[doc = "pure"]
+        fn scale(&self, a: usize) -> usize {
+            a * 3
+        }
+    }
+
+    impl<T: Copy + Into<usize>> Scaler for Wrapper<T> {
+        #[doc = "pure"]
+        fn scale(&self, a: usize) -> usize {
+            a * self.0.into()
+        }
+    }
+
+    // The receiver could be any instance of `Wrapper`, which is never analyzed.
+    #[doc = "impure"]
+    fn erased_generic_helper(a: usize, dynamic: &dyn Scaler) -> usize {
+        dynamic.scale(a)
+    }
 }
 
 mod returns_impl_fn {
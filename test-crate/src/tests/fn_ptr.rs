@@ -11,6 +11,24 @@ mod fn_ptr_coercion {
     }
 }
 
+mod fn_ptr_leak {
+    #[doc = "impure"]
+    pub fn bar(data: usize) -> usize {
+        println!("{}", data);
+        data
+    }
+
+    #[doc = "impure"]
+    pub fn fn_to_leaky_fn_ptr(data: usize) -> usize {
+        let fn_ptr: fn(usize) -> usize = if data == 0 {
+            super::fn_ptr_coercion::foo
+        } else {
+            bar
+        };
+        fn_ptr(data)
+    }
+}
+
 mod fmt {
   #[doc = "pure"]
   pub fn format(data: usize) -> String {
@@ -4,3 +4,29 @@ use chrono::naive::NaiveDateTime;
 pub fn date_format(v: NaiveDateTime) -> String {
     v.format("%Y-%m-%d %H:%M:%S").to_string()
 }
+
+mod ffi {
+    extern "C" {
+        fn sqrt(x: f64) -> f64;
+        fn write(fd: i32, buf: *const u8, count: usize) -> isize;
+    }
+
+    #[doc = "pure"]
+    pub fn libm_sqrt(x: f64) -> f64 {
+        unsafe { sqrt(x) }
+    }
+
+    #[doc = "impure"]
+    pub fn libc_write(buf: &[u8]) -> isize {
+        unsafe { write(1, buf.as_ptr(), buf.len()) }
+    }
+}
+
+mod inline_asm {
+    use std::arch::asm;
+
+    #[doc = "impure"]
+    pub fn asm_block(a: u64) {
+        unsafe { asm!("/* {0} */", in(reg) a) };
+    }
+}
@@ -0,0 +1,29 @@
+mod generic_pure {
+    #[doc = "pure"]
+    pub fn add<T: std::ops::Add<Output = T>>(a: T, b: T) -> T {
+        a + b
+    }
+
+    #[doc = "pure"]
+    pub fn add_usize(a: usize, b: usize) -> usize {
+        add(a, b)
+    }
+
+    #[doc = "pure"]
+    pub fn add_u8(a: u8, b: u8) -> u8 {
+        add(a, b)
+    }
+}
+
+mod generic_leaky {
+    #[doc = "impure"]
+    pub fn leak<T: std::fmt::Debug>(data: T) -> T {
+        println!("{:?}", data);
+        data
+    }
+
+    #[doc = "impure"]
+    pub fn leak_usize(data: usize) -> usize {
+        leak(data)
+    }
+}
@@ -17,19 +17,19 @@ mod closure_tests {
     }
 
     #[inline(never)]
-    #[doc = "impure"]
+    #[doc = "pure"]
     pub fn execute_once<F: FnOnce(usize) -> usize>(x: usize, l: F) -> usize {
         l(x)
     }
 
     #[inline(never)]
-    #[doc = "impure"]
+    #[doc = "pure"]
     pub fn execute_mut<F: FnMut(usize) -> usize>(x: usize, mut l: F) -> usize {
         l(x)
     }
 
     #[inline(never)]
-    #[doc = "impure"]
+    #[doc = "pure"]
     pub fn execute<F: Fn(usize) -> usize>(x: usize, l: F) -> usize {
         l(x)
     }
@@ -86,6 +86,23 @@ mod closure_tests {
         execute_dyn(a, &closure_capture_move);
         execute_dyn(a, &ambiguous_lambda);
     }
+
+    // The instantiations of the helpers above are pure for pure closures only, so the ones with a
+    // leaking closure are annotated at the call site.
+    #[doc = "impure"]
+    pub fn leaky_closure_test(a: usize) {
+        let leaky_lambda = |x: usize| -> usize {
+            println!("{}", x);
+            x
+        };
+
+        #[scrutinizer::impure]
+        let _ = execute_once(a, leaky_lambda);
+        #[scrutinizer::impure]
+        let _ = execute_mut(a, leaky_lambda);
+        #[scrutinizer::impure]
+        let _ = execute(a, leaky_lambda);
+    }
 }
 
 mod resolving_opaque {
@@ -17,12 +17,47 @@ mod network {
 }
 
 mod interior {
-    use std::cell::RefCell;
+    use std::cell::{Cell, RefCell};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
 
     #[doc = "impure"]
     pub fn ref_cell_mut(refcell: &RefCell<usize>) {
         *refcell.borrow_mut() = 10;
     }
+
+    // Only the value is sensitive, the cell is shared with the caller.
+    #[scrutinizer::impure]
+    #[scrutinizer::important(value)]
+    pub fn cell_set(cell: &Cell<usize>, value: usize) {
+        cell.set(value);
+    }
+
+    #[scrutinizer::pure]
+    #[scrutinizer::important(value)]
+    pub fn cell_reset(cell: &Cell<usize>, value: usize) -> usize {
+        cell.set(0);
+        value + 1
+    }
+
+    #[doc = "pure"]
+    pub fn cell_get(cell: &Cell<usize>) -> usize {
+        cell.get() + 1
+    }
+
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    #[doc = "impure"]
+    pub fn atomic_store(value: usize) {
+        COUNTER.store(value, Ordering::SeqCst);
+    }
+
+    static LOG: Mutex<Vec<usize>> = Mutex::new(vec![]);
+
+    #[doc = "impure"]
+    pub fn mutex_push(value: usize) {
+        LOG.lock().unwrap().push(value);
+    }
 }
 
 mod implicit {
@@ -31,6 +66,7 @@ mod implicit {
     }
 
     impl Drop for CustomSmartPointer {
+        #[doc = "impure"]
         fn drop(&mut self) {
             println!("Dropping CustomSmartPointer with data `{}`!", self.data);
         }
@@ -43,6 +79,7 @@ mod implicit {
 }
 
 mod adversarial {
+    use std::mem::ManuallyDrop;
     use std::ptr;
 
     #[doc = "impure"]
@@ -70,4 +107,43 @@ mod adversarial {
         let sink_mut: [&mut u32; 1] = unsafe { std::mem::transmute(sink) };
         *sink_mut[0] = value;
     }
+
+    #[doc = "impure"]
+    fn transmute_ref(value: u32, sink: &u32) {
+        let sink_mut = unsafe { std::mem::transmute::<&u32, &mut u32>(sink) };
+        *sink_mut = value;
+    }
+
+    #[doc = "impure"]
+    fn transmute_copy_ref(value: u32, sink: &u32) {
+        let sink_mut: &mut u32 = unsafe { std::mem::transmute_copy(&sink) };
+        *sink_mut = value;
+    }
+
+    #[doc = "impure"]
+    fn const_to_mut_cast(value: u32, sink: &u32) {
+        let sink_mut = sink as *const u32 as *mut u32;
+        unsafe { *sink_mut = value };
+    }
+
+    // Casting a pointer that was mutable to begin with launders nothing.
+    #[doc = "pure"]
+    fn mut_to_const_to_mut_cast(value: u32) -> u32 {
+        let mut local = value;
+        let local_mut = &mut local as *mut u32 as *const u32 as *mut u32;
+        value + 1
+    }
+
+    // Mutable references need `ManuallyDrop` to be stored in a union. The write goes through a
+    // reference, so only the laundering makes the function impure.
+    union Launderer<'a> {
+        shared: &'a u32,
+        exclusive: ManuallyDrop<&'a mut u32>,
+    }
+
+    #[doc = "impure"]
+    fn union_read(value: u32, sink: &u32) {
+        let mut launderer = Launderer { shared: sink };
+        unsafe { **launderer.exclusive = value };
+    }
 }
\ No newline at end of file
@@ -0,0 +1,43 @@
+mod inherent_methods {
+    struct Counter {
+        count: usize,
+    }
+
+    impl Counter {
+        #[doc = "pure"]
+        fn incremented(&self, a: usize) -> usize {
+            self.count + a
+        }
+
+        #[doc = "impure"]
+        fn leaky_incremented(&self, a: usize) -> usize {
+            println!("{}", a);
+            self.count + a
+        }
+    }
+}
+
+mod default_trait_methods {
+    trait Scaler {
+        fn factor(&self) -> usize;
+
+        #[doc = "pure"]
+        fn scale(&self, a: usize) -> usize {
+            a * self.factor()
+        }
+    }
+
+    struct Doubler;
+
+    impl Scaler for Doubler {
+        #[doc = "pure"]
+        fn factor(&self) -> usize {
+            2
+        }
+    }
+
+    #[doc = "pure"]
+    fn scale_by_two(a: usize) -> usize {
+        Doubler.scale(a)
+    }
+}
@@ -1,8 +1,13 @@
+mod r#async;
+mod attrs;
 mod r#dyn;
 mod fn_ptr;
 mod foreign;
+mod generics;
 mod lam;
 mod leaky;
+mod methods;
+mod mut_args;
 mod raw_ptr;
 mod recursive;
 mod r#static;
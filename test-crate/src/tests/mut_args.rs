@@ -0,0 +1,19 @@
+// Expectations for `allow_mut_args = true`, only checked by `scrutinizer-config-mut-args.toml`.
+// Without it, `normalize` is rejected by the precheck.
+mod output_sinks {
+    pub struct Key([u8; 16]);
+
+    // The secret only ends up in the buffer, which is an output of the function.
+    #[doc = "pure"]
+    pub fn normalize(buf: &mut Vec<u8>, secret: &Key) {
+        buf.clear();
+        buf.extend_from_slice(&secret.0);
+    }
+
+    #[doc = "impure"]
+    pub fn normalize_leaky(buf: &mut Vec<u8>, secret: &Key) {
+        buf.clear();
+        buf.extend_from_slice(&secret.0);
+        println!("{:?}", buf);
+    }
+}
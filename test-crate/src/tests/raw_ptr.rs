@@ -23,6 +23,7 @@ mod raw_mut_ptr_call {
     }
 
     impl Foo {
+        #[doc = "impure"]
         fn amend(&mut self) {
             self.x = 42;
         }
@@ -56,3 +57,33 @@ mod raw_const_ptr {
         let _points_at = *raw;
     }
 }
+
+mod shared_ref_raw_mut_ptr {
+    // Writing important data through a raw mut pointer obtained from a shared reference.
+    #[doc = "impure"]
+    pub unsafe fn shared_ref_raw_mut_ptr_deref(a: usize, sink: &usize) {
+        let raw = sink as *const usize as *mut usize;
+        *raw = a;
+    }
+}
+
+// Expectations for `raw_ptr_heuristic = "dataflow"`, only checked by
+// `scrutinizer-config-dataflow.toml`. The default coarse heuristic rejects `push_to_owned_vec`.
+mod dataflow {
+    // Writing important data into an allocation owned by the region.
+    #[doc = "pure"]
+    pub unsafe fn push_to_owned_vec(a: usize) -> Vec<usize> {
+        let mut buffer: Vec<usize> = Vec::with_capacity(1);
+        let slot = buffer.as_mut_ptr().add(buffer.len());
+        *slot = a;
+        buffer.set_len(buffer.len() + 1);
+        buffer
+    }
+
+    // Writing important data into memory the caller only lent immutably.
+    #[doc = "impure"]
+    pub unsafe fn write_through_shared_ref(a: usize, sink: &usize) {
+        let raw = sink as *const usize as *mut usize;
+        *raw = a;
+    }
+}
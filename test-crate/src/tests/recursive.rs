@@ -46,3 +46,24 @@ mod mutually_recursive {
         println!("{}", a);
     }
 }
+
+mod depth_limit {
+    // Repeat the given statements twice.
+    macro_rules! twice {
+        ($($tokens:tt)*) => {
+            $($tokens)*
+            $($tokens)*
+        };
+    }
+
+    // Every closure is pure, but the chain of 256 closures calling each other is deeper than the
+    // recursion limit, so the analysis gives up on it.
+    #[doc = "impure"]
+    fn deep_chain(a: usize) -> usize {
+        let f = |a: usize| a;
+        twice! { twice! { twice! { twice! { twice! { twice! { twice! { twice! {
+            let f = move |a: usize| f(a);
+        } } } } } } } }
+        f(a)
+    }
+}
@@ -9,10 +9,47 @@ mod mutable_static {
     }
 }
 
+mod thread_local_state {
+    #[thread_local]
+    static mut LAST_VALUE: u32 = 0;
+
+    #[doc = "impure"]
+    fn leak_into_thread_local(a: u32) {
+        unsafe {
+            LAST_VALUE = a;
+        }
+    }
+}
+
+mod thread_local_key {
+    use std::cell::Cell;
+
+    thread_local! {
+        static LAST_VALUE: Cell<u32> = Cell::new(0);
+    }
+
+    #[doc = "impure"]
+    fn leak_with_set(a: u32) {
+        LAST_VALUE.set(a);
+    }
+
+    #[doc = "impure"]
+    fn leak_with_replace(a: u32) -> u32 {
+        LAST_VALUE.replace(a)
+    }
+
+    #[doc = "pure"]
+    fn reset(a: u32) -> u32 {
+        LAST_VALUE.set(0);
+        a + 1
+    }
+}
+
 mod mutation_from_static {
     struct PureIncrementer;
 
     impl PureIncrementer {
+        #[doc = "pure"]
         fn inc(&self, a: usize) -> usize {
             a + 1
         }
@@ -21,6 +58,7 @@ mod mutation_from_static {
     struct ImpureIncrementer;
 
     impl ImpureIncrementer {
+        #[doc = "impure"]
         fn inc(&self, a: usize) -> usize {
             println!("{}", a);
             a + 1